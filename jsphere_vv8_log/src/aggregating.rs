@@ -3,40 +3,68 @@ use super::*;
 #[pub_fields]
 #[derive(Clone, Debug, Default)]
 pub struct RecordAggregate {
-    scripts: HashMap<i32, ScriptAggregate>,
+    /// Scripts keyed by their IDs namespaced by isolate.
+    scripts: HashMap<ScriptId, ScriptAggregate>,
+    /// Address of the current isolate, or 0 before any `~` record.
+    current_isolate: i64,
+    /// The latest known `window.origin` of each isolate.
+    origins: HashMap<i64, String>,
     current_script_id: i32,
-    interaction_injected: bool,
+    /// The phase of the latest record.
+    phase: Phase,
+    /// Each phase after [Phase::Load], which starts at line 0,
+    /// in order.
+    phase_starts: Vec<PhaseStart>,
+    /// Number of [Phase::Interaction]s started so far.
+    n_interaction: u32,
+    /// Opt in to keeping [ApiCall::args] for function and construction calls.
+    summarize_arguments: bool,
+    /// Opt in to keeping [ApiCall::value] for property sets.
+    summarize_set_values: bool,
+    /// Decides which new scripts are [ScriptInjectionType::Interaction].
+    interaction_detector: InteractionDetector,
 }
 
 impl RecordAggregate {
-    pub fn add(&mut self, line: u32, record: LogRecord) -> Result<()> {
-        let maybe_get_set = match record {
-            LogRecord::IsolateContext { address } => {
-                debug!(line, "Ignoring isolate context {address:#x}");
+    /// Aggregate the `record` at `line`. Borrowed [LogRecordRef]s
+    /// only allocate for the strings kept, e.g., new API calls.
+    pub fn add<'a>(&mut self, line: u32, record: impl Into<LogRecordRef<'a>>) -> Result<()> {
+        let maybe_get_set = match record.into() {
+            LogRecordRef::IsolateContext { address } => {
+                self.current_isolate = address;
                 None
             }
 
-            LogRecord::WindowOrigin { value } => {
-                debug!(line, ?value, "Ignoring window.origin");
+            LogRecordRef::WindowOrigin { value } => {
+                match value {
+                    JSValueRef::String(origin) => {
+                        self.origins
+                            .insert(self.current_isolate, origin.into_owned());
+                    }
+                    _ => {
+                        debug!(line, ?value, "Unknown window.origin");
+                        self.origins.remove(&self.current_isolate);
+                    }
+                }
                 None
             }
 
-            LogRecord::ScriptProvenance { id, name, source } => {
-                let name = name.try_into()?;
+            LogRecordRef::ScriptProvenance { id, name, source } => {
+                let name = name.into_owned().try_into()?;
                 let is_injected = match name {
                     ScriptName::Empty => true,
                     ScriptName::Url(_) => false,
                     ScriptName::Eval { parent_script_id } => matches!(
                         self.scripts
-                            .get(&parent_script_id)
+                            .get(&self.script_id(parent_script_id))
                             .context("Unknown parent script ID")?
                             .injection_type,
                         ScriptInjectionType::Injected | ScriptInjectionType::Interaction
                     ),
                 };
-                // NOTE: Hardcoding interaction script check for now.
                 let is_interaction =
-                    is_injected && source[..source.len().min(100)].contains("Gremlins horde");
+                    self.interaction_detector
+                        .is_interaction(&name, &source, is_injected);
                 let injection_type = match (is_interaction, is_injected) {
                     (true, _) => ScriptInjectionType::Interaction,
                     (_, true) => ScriptInjectionType::Injected,
@@ -45,10 +73,14 @@ impl RecordAggregate {
                 let script = ScriptAggregate {
                     line,
                     name,
-                    source,
+                    effective_len: parse_effective_len(&source),
+                    rewritten_blocks: RewrittenBlock::find_all(&source),
+                    source: source.into_owned(),
                     injection_type,
+                    origin: self.current_origin().map(Into::into),
                     ..Default::default()
                 };
+                let id = self.script_id(id);
                 if let Some(prev_script) = self.scripts.insert(id, script) {
                     bail!("Overwrote script {id}: {prev_script:?}");
                 }
@@ -56,26 +88,36 @@ impl RecordAggregate {
             }
 
             // Ignore unsure execution contexts.
-            LogRecord::ExecutionContext { script_id } if script_id == ID_UNSURE => None,
+            LogRecordRef::ExecutionContext { script_id } if script_id == ID_UNSURE => None,
 
-            LogRecord::ExecutionContext { script_id } => {
+            LogRecordRef::ExecutionContext { script_id } => {
                 self.current_script_id = script_id;
+                let origin = self.current_origin().map(String::from);
                 let script = self.current_script()?;
-                if matches!(script.injection_type, ScriptInjectionType::Interaction) {
-                    // Entering a context with an interaction script is
-                    // the only way we know an interaction started for sure.
-                    self.interaction_injected = true;
+                if script.exec_origin.is_none() {
+                    script.exec_origin = origin;
+                }
+                let injection_type = script.injection_type.clone();
+                // Entering a context with an interaction script is
+                // the only way we know an interaction started for sure.
+                let phase = self.phase.enter(&injection_type, self.n_interaction);
+                if phase != self.phase {
+                    if let Phase::Interaction(_) = phase {
+                        self.n_interaction += 1;
+                    }
+                    self.phase = phase;
+                    self.phase_starts.push(PhaseStart { phase, line });
                 }
                 None
             }
 
             // Ignore user function calls or function calls with
             // a placeholder offset.
-            LogRecord::FunctionCall {
+            LogRecordRef::FunctionCall {
                 is_user_fn: true, ..
             }
-            | LogRecord::FunctionCall { offset: -1, .. }
-            | LogRecord::ConstructionCall {
+            | LogRecordRef::FunctionCall { offset: -1, .. }
+            | LogRecordRef::ConstructionCall {
                 is_user_fn: true, ..
             } => {
                 if let Ok(script) = self.current_script() {
@@ -84,34 +126,35 @@ impl RecordAggregate {
                 None
             }
 
-            LogRecord::FunctionCall {
-                // Ignore arguments, etc. for now.
+            LogRecordRef::FunctionCall {
+                offset,
                 method,
                 receiver,
+                arguments,
                 ..
             } => {
                 let this = match receiver {
-                    JSValue::Object { constructor, .. } => Some(constructor),
-                    JSValue::Function { name, is_user_fn } => {
+                    JSValueRef::Object { constructor, .. } => Some(constructor),
+                    JSValueRef::Function { name, is_user_fn } => {
                         match is_user_fn {
                             true => None, // Ignore user functions.
                             false => Some(name),
                         }
                     }
 
-                    JSValue::Lambda
-                    | JSValue::V8Specific
-                    | JSValue::ObjectUnknown(_)
-                    | JSValue::ObjectLiteral { .. }
-                    | JSValue::Unsure => None, // Ignore internal calls or calls of user-defined functions.
-
-                    JSValue::String(_)
-                    | JSValue::Int(_)
-                    | JSValue::Float(_)
-                    | JSValue::RegEx(_)
-                    | JSValue::Boolean(_)
-                    | JSValue::Null
-                    | JSValue::Undefined => {
+                    JSValueRef::Lambda
+                    | JSValueRef::V8Specific
+                    | JSValueRef::ObjectUnknown(_)
+                    | JSValueRef::ObjectLiteral { .. }
+                    | JSValueRef::Unsure => None, // Ignore internal calls or calls of user-defined functions.
+
+                    JSValueRef::String(_)
+                    | JSValueRef::Int(_)
+                    | JSValueRef::Float(_)
+                    | JSValueRef::RegEx(_)
+                    | JSValueRef::Boolean(_)
+                    | JSValueRef::Null
+                    | JSValueRef::Undefined => {
                         if method != "Function" {
                             Some("".into()) // Record empty string for static functions.
                         } else {
@@ -123,54 +166,69 @@ impl RecordAggregate {
                 if let Some(this) = this {
                     let api_call = ApiCall {
                         api_type: ApiType::Function,
-                        this,
-                        attr: Some(method),
+                        this: this.into_owned(),
+                        attr: Some(method.into_owned()),
+                        args: self.summarize(&arguments),
+                        value: None,
                     };
-                    self.push_api_call(api_call, line)?;
+                    self.push_api_call(api_call, line, offset)?;
                 } else {
                     self.current_script()?.n_filtered_call += 1;
                 }
                 None
             }
 
-            LogRecord::ConstructionCall {
-                // Ignore arguments, etc. for now.
+            LogRecordRef::ConstructionCall {
+                offset,
                 method,
+                arguments,
                 ..
             } => {
                 let api_call = ApiCall {
                     api_type: ApiType::Construction,
-                    this: method,
+                    this: method.into_owned(),
                     attr: None,
+                    args: self.summarize(&arguments),
+                    value: None,
                 };
-                self.push_api_call(api_call, line)?;
+                self.push_api_call(api_call, line, offset)?;
                 None
             }
 
-            LogRecord::GetProperty {
-                object, property, ..
-            } => Some((ApiType::Get, object, property)),
+            LogRecordRef::GetProperty {
+                offset,
+                object,
+                property,
+            } => Some((ApiType::Get, offset, object, property, None)),
 
-            LogRecord::SetProperty {
-                object, property, ..
-            } => Some((ApiType::Set, object, property)),
+            LogRecordRef::SetProperty {
+                offset,
+                object,
+                property,
+                value,
+            } => {
+                let value = self
+                    .summarize_set_values
+                    .then(|| ValueKind::from(&value).without_literal());
+                Some((ApiType::Set, offset, object, property, value))
+            }
         };
 
         // Handle get/set calls after all other types.
-        if let Some((api_type, object, property)) = maybe_get_set {
+        if let Some((api_type, offset, object, property, value)) = maybe_get_set {
             let this = match object {
-                JSValue::Object { constructor, .. } => Some(constructor),
-                JSValue::ObjectLiteral { .. } => None, // Ignore object literals.
+                JSValueRef::Object { constructor, .. } => Some(constructor),
+                JSValueRef::ObjectLiteral { .. } => None, // Ignore object literals.
                 _ => bail!("{line}: Unexpected get/set on object: {object:?}"),
             };
             let maybe_this_attr = if let Some(this) = this {
                 let attr = match property {
-                    JSValue::String(attr) => Some(attr),
+                    JSValueRef::String(attr) => Some(attr),
                     // Ignore getting/setting user-defined or internal values.
-                    JSValue::Object { .. }
-                    | JSValue::Int(_)
-                    | JSValue::Float(_)
-                    | JSValue::Unsure => None,
+                    JSValueRef::Object { .. }
+                    | JSValueRef::Int(_)
+                    | JSValueRef::Float(_)
+                    | JSValueRef::Unsure => None,
                     _ => bail!("{line}: Unexpected get/set property: {property:?}"),
                 };
                 attr.map(|attr| (this, attr))
@@ -180,10 +238,12 @@ impl RecordAggregate {
             if let Some((this, attr)) = maybe_this_attr {
                 let api_call = ApiCall {
                     api_type,
-                    this,
-                    attr: Some(attr),
+                    this: this.into_owned(),
+                    attr: Some(attr.into_owned()),
+                    args: Vec::new(),
+                    value,
                 };
-                self.push_api_call(api_call, line)?;
+                self.push_api_call(api_call, line, offset)?;
             } else {
                 self.current_script()?.n_filtered_call += 1
             }
@@ -191,15 +251,17 @@ impl RecordAggregate {
         Ok(())
     }
 
-    fn push_api_call(&mut self, api_call: ApiCall, line: u32) -> Result<()> {
-        let may_interact = self.interaction_injected;
+    fn push_api_call(&mut self, api_call: ApiCall, line: u32, offset: i32) -> Result<()> {
+        let phase = self.phase;
         let current_script = self.current_script()?;
         if api_call.likely_browser_api() {
             let lines = current_script.api_calls.entry(api_call).or_default();
-            if may_interact && lines.i_may_interact.is_none() {
+            if phase.may_interact() && lines.i_may_interact.is_none() {
                 lines.i_may_interact = Some(lines.lines.len() as u32);
             }
             lines.lines.push(line);
+            lines.offsets.push(offset);
+            lines.phases.push(phase);
         } else {
             current_script.n_filtered_call += 1;
         }
@@ -208,9 +270,83 @@ impl RecordAggregate {
 
     fn current_script(&mut self) -> Result<&mut ScriptAggregate> {
         self.scripts
-            .get_mut(&self.current_script_id)
+            .get_mut(&self.script_id(self.current_script_id))
             .context("Unknown execution context script ID")
     }
+
+    /// Summarize `arguments` if opted in by
+    /// [RecordAggregate::summarize_arguments].
+    fn summarize(&self, arguments: &[JSValueRef]) -> Vec<ValueKind> {
+        match self.summarize_arguments {
+            true => arguments.iter().map(Into::into).collect(),
+            false => Vec::new(),
+        }
+    }
+
+    /// The latest known `window.origin` of the current isolate.
+    pub fn current_origin(&self) -> Option<&str> {
+        self.origins.get(&self.current_isolate).map(String::as_str)
+    }
+
+    /// Count API calls by the `window.origin` their scripts executed in,
+    /// e.g., to tell first-party page JS apart from cross-origin iframe JS.
+    /// Scripts with unknown origins are counted under `None`.
+    pub fn api_calls_by_origin(&self) -> HashMap<Option<&str>, HashMap<&ApiCall, u32>> {
+        let mut by_origin = HashMap::<_, HashMap<_, u32>>::new();
+        for script in self.scripts.values() {
+            let calls = by_origin.entry(script.active_origin()).or_default();
+            for (api_call, lines) in &script.api_calls {
+                *calls.entry(api_call).or_default() += lines.len();
+            }
+        }
+        by_origin
+    }
+
+    /// Namespace `id` by the current isolate.
+    fn script_id(&self, id: i32) -> ScriptId {
+        ScriptId {
+            isolate: self.current_isolate,
+            id,
+        }
+    }
+}
+
+/// A script ID, which VV8 only guarantees to be unique within an isolate.
+#[pub_fields]
+#[derive_everything]
+#[derive(Copy)]
+pub struct ScriptId {
+    /// Address of the isolate, e.g., `0x2a3800370000`.
+    isolate: i64,
+    /// Script ID within the isolate, e.g., 5.
+    id: i32,
+}
+
+impl std::fmt::Display for ScriptId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#x}:{}", self.isolate, self.id)
+    }
+}
+
+/// SHA-256 of a script's source, stable across runs and machines.
+/// Displays as lowercase hex.
+#[derive_everything]
+#[derive(Copy)]
+pub struct ContentHash(pub [u8; 32]);
+
+impl ContentHash {
+    pub fn of(source: &str) -> Self {
+        Self(Sha256::digest(source.as_bytes()).into())
+    }
+}
+
+impl std::fmt::Display for ContentHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
 }
 
 /// A script that was executed and its aggregate information.
@@ -223,22 +359,203 @@ pub struct ScriptAggregate {
     name: ScriptName,
     /// JS source code.
     source: String,
+    /// Length of the original code, ignoring nested `eval` blocks,
+    /// if the eval trick rewrote the script.
+    effective_len: Option<usize>,
+    /// Nested `eval` blocks the eval trick split the source into.
+    rewritten_blocks: Vec<RewrittenBlock>,
     injection_type: ScriptInjectionType,
+    /// `window.origin` of the isolate when the script was provided.
+    origin: Option<String>,
+    /// `window.origin` of the isolate when the script first executed.
+    exec_origin: Option<String>,
     /// API calls made, and the lines where they were made.
     api_calls: HashMap<ApiCall, CallLines>,
     /// API calls that are filtered out.
     n_filtered_call: u32,
 }
 
+impl ScriptAggregate {
+    /// The origin the script executed in, falling back to
+    /// the origin it was provided in.
+    pub fn active_origin(&self) -> Option<&str> {
+        self.exec_origin.as_deref().or(self.origin.as_deref())
+    }
+
+    /// Whether the script ran in an origin known to differ from
+    /// `page_origin`, e.g., in an ad or embed iframe.
+    pub fn is_cross_origin(&self, page_origin: &str) -> bool {
+        self.active_origin()
+            .is_some_and(|origin| origin != page_origin)
+    }
+
+    /// [ContentHash] of the unescaped source.
+    pub fn content_hash(&self) -> ContentHash {
+        ContentHash::of(&self.source)
+    }
+
+    /// Whether the eval trick rewrote the script.
+    pub fn is_rewritten(&self) -> bool {
+        self.effective_len.is_some()
+    }
+
+    /// Size of the script's own code, i.e., the effective length if
+    /// rewritten, or else the source length.
+    pub fn size(&self) -> usize {
+        self.effective_len.unwrap_or(self.source.len())
+    }
+
+    /// ID of the script that `eval`ed this script, if any.
+    /// `id` is this script's ID, whose isolate the parent shares.
+    pub fn parent_id(&self, id: ScriptId) -> Option<ScriptId> {
+        match self.name {
+            ScriptName::Eval { parent_script_id } => Some(ScriptId {
+                isolate: id.isolate,
+                id: parent_script_id,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Locate the character `offset` of an API call (see [CallLines::offsets])
+    /// in `source`. VV8 counts offsets in UTF-16 code units.
+    pub fn position(&self, offset: i32) -> Option<SourcePosition> {
+        let offset = usize::try_from(offset).ok()?;
+        let (mut utf16_index, mut line, mut line_start) = (0, 1, 0);
+        for (byte, char) in self.source.char_indices() {
+            if utf16_index >= offset {
+                let column = self.source[line_start..byte].chars().count() + 1;
+                return (utf16_index == offset).then_some(SourcePosition { byte, line, column });
+            }
+            utf16_index += char.len_utf16();
+            if char == '\n' {
+                line += 1;
+                line_start = byte + 1;
+            }
+        }
+        None
+    }
+
+    /// The code on the same line around the character `offset`,
+    /// at most `radius` characters on either side.
+    pub fn snippet(&self, offset: i32, radius: usize) -> Option<&str> {
+        let SourcePosition { byte, .. } = self.position(offset)?;
+        let (before, after) = self.source.split_at(byte);
+        let line_before = before.rsplit('\n').next().unwrap_or("");
+        let line_after = after.split('\n').next().unwrap_or("");
+        let start = line_before
+            .char_indices()
+            .rev()
+            .take(radius)
+            .last()
+            .map_or(line_before.len(), |(index, _)| index);
+        let end = line_after
+            .char_indices()
+            .nth(radius)
+            .map_or(line_after.len(), |(index, _)| index);
+        Some(&self.source[byte - line_before.len() + start..byte + end])
+    }
+}
+
+/// The `//N effectiveLen` header the eval trick prepends to
+/// every context it rewrites.
+fn parse_effective_len(source: &str) -> Option<usize> {
+    regex_captures!(r"^//(\d+) effectiveLen", source).and_then(|(_, len)| len.parse().ok())
+}
+
+/// A nested `eval` block the eval trick emitted into a script's source.
+#[pub_fields]
+#[derive_everything]
+#[derive(Copy)]
+pub struct RewrittenBlock {
+    /// Byte index in the source where the block's
+    /// `//N effectiveLen` header starts.
+    byte: usize,
+    /// Byte index of the block's closing backtick, so
+    /// `source[byte..end]` is the block's code, or
+    /// the source length if the block is unterminated.
+    end: usize,
+    /// Length of the block's original code, ignoring nested blocks.
+    effective_len: usize,
+}
+
+impl RewrittenBlock {
+    /// Find the blocks in `source`, both top-level `eval(String.raw`…`)`
+    /// and nested `${迤(String.raw`…`)}` ones, in source order.
+    pub fn find_all(source: &str) -> Vec<Self> {
+        regex!(r"(?:\beval|\$\{迤)\(String\.raw`(//(\d+) effectiveLen)")
+            .captures_iter(source)
+            .filter_map(|captures| {
+                let header = captures.get(1)?;
+                Some(Self {
+                    byte: header.start(),
+                    end: find_block_end(source, header.start()),
+                    effective_len: captures[2].parse().ok()?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Find the backtick closing the `String.raw` template whose body starts at
+/// `start`. Inside, the eval trick escapes backticks and `${` as
+/// `${'`'}` and `${'${'}`, and nested blocks open with ``String.raw` ``.
+fn find_block_end(source: &str, start: usize) -> usize {
+    let bytes = source.as_bytes();
+    let mut depth = 1;
+    let mut index = start;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 1,
+            b'$' if bytes[index..].starts_with(b"${'") => match source[index + 3..].find("'}") {
+                Some(quote_end) => index += 3 + quote_end + 1,
+                None => return source.len(),
+            },
+            b'`' if source[..index].ends_with("String.raw") => depth += 1,
+            b'`' => {
+                depth -= 1;
+                if depth == 0 {
+                    return index;
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    source.len()
+}
+
+/// A position in a script source.
+#[pub_fields]
+#[derive_everything]
+#[derive(Copy)]
+pub struct SourcePosition {
+    /// Byte index in the source.
+    byte: usize,
+    /// Line number from 1.
+    line: usize,
+    /// Column number in characters from 1.
+    column: usize,
+}
+
 /// A browser JS API call.
 ///
-/// Arguments are ignored.
+/// Arguments and set values are ignored unless
+/// [RecordAggregate::summarize_arguments] and
+/// [RecordAggregate::summarize_set_values] are set, respectively.
 #[pub_fields]
 #[derive_everything]
 pub struct ApiCall {
     api_type: ApiType,
     this: String,
     attr: Option<String>,
+    /// Summary of the arguments, if opted in.
+    args: Vec<ValueKind>,
+    /// Kind of the value assigned by a set, if opted in, e.g.,
+    /// [ValueKind::Function] for `onclick = function() {...}`.
+    /// String contents are dropped (see [ValueKind::without_literal]) so
+    /// assigning different strings does not split the call.
+    value: Option<ValueKind>,
 }
 
 impl ApiCall {
@@ -256,17 +573,87 @@ impl ApiCall {
                 None => true,
             }
     }
+
+    /// Whether the call reads or changes where the page is, i.e.,
+    /// any use of `Location` or `History`, e.g., `location.href` gets and
+    /// `history.pushState` calls.
+    pub fn is_navigation(&self) -> bool {
+        matches!(self.this.as_str(), "Location" | "History")
+    }
 }
 
 fn match_browser_api_name(name: &str) -> bool {
     regex_is_match!(r"^([A-Za-z\. ]+[0-9]{0,3})+$", name)
 }
 
+/// Normalized summary of a [JSValue], e.g., an argument in an [ApiCall].
+#[derive_everything]
+pub enum ValueKind {
+    /// String, with its content if at most [MAX_LITERAL_LEN] bytes long,
+    /// e.g., tag names, event types, and storage keys.
+    String(Option<String>),
+    Number,
+    RegEx,
+    Boolean,
+    Null,
+    Undefined,
+    /// Function, user-defined or not, including anonymous ones.
+    Function,
+    /// Object, with the name of the constructor function if known.
+    Object(Option<String>),
+    /// V8-specific or unsure values.
+    #[default]
+    Unknown,
+}
+
+impl ValueKind {
+    /// Drop the content of [ValueKind::String], keeping only the kind.
+    pub fn without_literal(self) -> Self {
+        match self {
+            Self::String(_) => Self::String(None),
+            kind => kind,
+        }
+    }
+}
+
+/// Strings longer than this are not kept in [ValueKind::String].
+pub const MAX_LITERAL_LEN: usize = 32;
+
+impl From<&JSValueRef<'_>> for ValueKind {
+    fn from(value: &JSValueRef) -> Self {
+        match value {
+            JSValueRef::String(s) => {
+                Self::String((s.len() <= MAX_LITERAL_LEN).then(|| s.to_string()))
+            }
+            JSValueRef::Int(_) | JSValueRef::Float(_) => Self::Number,
+            JSValueRef::RegEx(_) => Self::RegEx,
+            JSValueRef::Boolean(_) => Self::Boolean,
+            JSValueRef::Null => Self::Null,
+            JSValueRef::Undefined => Self::Undefined,
+            JSValueRef::Function { .. } | JSValueRef::Lambda => Self::Function,
+            JSValueRef::Object { constructor, .. } => Self::Object(Some(constructor.to_string())),
+            JSValueRef::ObjectUnknown(_) | JSValueRef::ObjectLiteral { .. } => Self::Object(None),
+            JSValueRef::V8Specific | JSValueRef::Unsure => Self::Unknown,
+        }
+    }
+}
+
+impl From<&JSValue> for ValueKind {
+    fn from(value: &JSValue) -> Self {
+        Self::from(&JSValueRef::from(value.clone()))
+    }
+}
+
 /// Lines where API calls were made.
 #[pub_fields]
 #[derive_everything]
 pub struct CallLines {
     lines: Vec<u32>,
+    /// Character offsets within the script source, one per line in `lines`.
+    /// See [ScriptAggregate::position] to map them to source code.
+    offsets: Vec<i32>,
+    /// The phase of each call in `lines`.
+    phases: Vec<Phase>,
     /// The index in `lines`, starting from which there may be interactions.
     i_may_interact: Option<u32>,
 }
@@ -276,6 +663,52 @@ impl CallLines {
         self.lines.len() as u32
     }
 
+    /// Merge `other`'s calls into these, keeping them ordered by line.
+    /// Errors if `lines`, `offsets`, and `phases` differ in length in
+    /// either operand, e.g., for a partially hand-built [CallLines].
+    pub fn merge(&mut self, other: &Self) -> Result<()> {
+        for lines in [&*self, other] {
+            if lines.lines.len() != lines.offsets.len() || lines.lines.len() != lines.phases.len() {
+                bail!(
+                    "Merging CallLines with {} lines, {} offsets, and {} phases",
+                    lines.lines.len(),
+                    lines.offsets.len(),
+                    lines.phases.len(),
+                );
+            }
+        }
+        let mut calls: Vec<_> = [&*self, other]
+            .into_iter()
+            .flat_map(|lines| {
+                lines
+                    .lines
+                    .iter()
+                    .zip(&lines.offsets)
+                    .zip(&lines.phases)
+                    .map(|((&line, &offset), &phase)| (line, offset, phase))
+            })
+            .collect();
+        calls.sort_unstable();
+        self.lines = calls.iter().map(|(line, _, _)| *line).collect();
+        self.offsets = calls.iter().map(|(_, offset, _)| *offset).collect();
+        self.phases = calls.iter().map(|(_, _, phase)| *phase).collect();
+        self.i_may_interact = self
+            .phases
+            .iter()
+            .position(|phase| phase.may_interact())
+            .map(|i| i as u32);
+        Ok(())
+    }
+
+    /// Number of calls in each phase.
+    pub fn counts_by_phase(&self) -> BTreeMap<Phase, u32> {
+        let mut counts = BTreeMap::new();
+        for &phase in &self.phases {
+            *counts.entry(phase).or_default() += 1;
+        }
+        counts
+    }
+
     pub fn n_may_interact(&self) -> u32 {
         self.len() - self.n_must_not_interact()
     }
@@ -287,6 +720,7 @@ impl CallLines {
 
 /// The type of API call.
 #[derive_everything]
+#[derive(Deserialize)]
 pub enum ApiType {
     #[default]
     Function,
@@ -331,3 +765,24 @@ impl TryFrom<JSValue> for ScriptName {
         })
     }
 }
+
+#[cfg(test)]
+impl RecordAggregate {
+    /// Aggregate the log `lines`, numbered from 0.
+    pub(crate) fn add_lines(&mut self, lines: &[&str]) -> Result<()> {
+        for (line, record) in lines.iter().enumerate() {
+            self.add(line as u32, LogRecordRef::try_from(*record)?)?;
+        }
+        Ok(())
+    }
+
+    /// [RecordAggregate::add_lines] with default settings.
+    pub(crate) fn from_lines(lines: &[&str]) -> Result<Self> {
+        let mut aggregate = Self::default();
+        aggregate.add_lines(lines)?;
+        Ok(aggregate)
+    }
+}
+
+#[cfg(test)]
+mod tests;
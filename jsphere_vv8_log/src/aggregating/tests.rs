@@ -0,0 +1,261 @@
+use super::*;
+
+#[test]
+fn script_ids_per_isolate() {
+    let aggregate = RecordAggregate::from_lines(&[
+        "~0x1",
+        r#"$5:"https\://a.com/a.js":atob("")"#,
+        "~0x2",
+        r#"$5:"https\://b.com/b.js":btoa("")"#,
+        r#"$6:5:btoa("")"#,
+        "!6",
+        r#"c9:%btoa:{1,Window}:"""#,
+        "~0x1",
+        "!5",
+        r#"c9:%atob:{1,Window}:"""#,
+    ])
+    .unwrap();
+    assert_eq!(aggregate.scripts.len(), 3);
+    let a = &aggregate.scripts[&ScriptId { isolate: 1, id: 5 }];
+    assert_eq!(a.name, ScriptName::Url("https://a.com/a.js".into()));
+    assert_eq!(a.api_calls.len(), 1);
+    let eval = &aggregate.scripts[&ScriptId { isolate: 2, id: 6 }];
+    assert_eq!(
+        eval.name,
+        ScriptName::Eval {
+            parent_script_id: 5
+        }
+    );
+    assert_eq!(eval.api_calls.len(), 1);
+
+    let err = RecordAggregate::from_lines(&[
+        "~0x1",
+        r#"$5:"https\://a.com/a.js":atob("")"#,
+        r#"$5:"https\://b.com/b.js":btoa("")"#,
+    ])
+    .unwrap_err();
+    assert!(err.to_string().starts_with("Overwrote script 0x1:5"));
+}
+
+#[test]
+fn window_origin_per_isolate() {
+    let aggregate = RecordAggregate::from_lines(&[
+        "~0x1",
+        r#"@"https\://a.com""#,
+        r#"$5:"https\://a.com/a.js":atob("")"#,
+        "~0x2",
+        r#"@"https\://ads.net""#,
+        r#"$5:"https\://ads.net/ad.js":atob("")"#,
+        "!5",
+        r#"c9:%atob:{1,Window}:"""#,
+        "~0x1",
+        "!5",
+        r#"c9:%atob:{1,Window}:"""#,
+        r#"c9:%atob:{1,Window}:"""#,
+    ])
+    .unwrap();
+    let a = &aggregate.scripts[&ScriptId { isolate: 1, id: 5 }];
+    assert_eq!(a.origin.as_deref(), Some("https://a.com"));
+    assert_eq!(a.exec_origin.as_deref(), Some("https://a.com"));
+    assert!(!a.is_cross_origin("https://a.com"));
+    let ad = &aggregate.scripts[&ScriptId { isolate: 2, id: 5 }];
+    assert_eq!(ad.active_origin(), Some("https://ads.net"));
+    assert!(ad.is_cross_origin("https://a.com"));
+
+    let by_origin = aggregate.api_calls_by_origin();
+    let atob = ApiCall {
+        api_type: ApiType::Function,
+        this: "Window".into(),
+        attr: Some("atob".into()),
+        ..Default::default()
+    };
+    assert_eq!(by_origin[&Some("https://a.com")][&atob], 2);
+    assert_eq!(by_origin[&Some("https://ads.net")][&atob], 1);
+}
+
+#[test]
+fn argument_summaries() {
+    let lines = [
+        r#"$5:"https\://a.com/a.js":x"#,
+        "!5",
+        r#"c1:%createElement:{1,HTMLDocument}:"div""#,
+        r#"c2:%createElement:{1,HTMLDocument}:"script""#,
+        r#"c3:%addEventListener:{2,HTMLDivElement}:"click":<anonymous>:{3,Object}"#,
+        r#"n4:%Image:100:#U"#,
+    ];
+    let mut aggregate = RecordAggregate::default();
+    aggregate.add_lines(&lines).unwrap();
+    let script = aggregate.scripts.values().next().unwrap();
+    assert_eq!(script.api_calls.len(), 3);
+    assert!(script
+        .api_calls
+        .keys()
+        .all(|api_call| api_call.args.is_empty()));
+
+    let mut aggregate = RecordAggregate {
+        summarize_arguments: true,
+        ..Default::default()
+    };
+    aggregate.add_lines(&lines).unwrap();
+    let script = aggregate.scripts.values().next().unwrap();
+    assert_eq!(script.api_calls.len(), 4);
+    let create_script = ApiCall {
+        api_type: ApiType::Function,
+        this: "HTMLDocument".into(),
+        attr: Some("createElement".into()),
+        args: vec![ValueKind::String(Some("script".into()))],
+        value: None,
+    };
+    assert!(script.api_calls.contains_key(&create_script));
+    let add_event_listener = ApiCall {
+        api_type: ApiType::Function,
+        this: "HTMLDivElement".into(),
+        attr: Some("addEventListener".into()),
+        args: vec![
+            ValueKind::String(Some("click".into())),
+            ValueKind::Function,
+            ValueKind::Object(Some("Object".into())),
+        ],
+        value: None,
+    };
+    assert!(script.api_calls.contains_key(&add_event_listener));
+    let image = ApiCall {
+        api_type: ApiType::Construction,
+        this: "Image".into(),
+        attr: None,
+        args: vec![ValueKind::Number, ValueKind::Undefined],
+        value: None,
+    };
+    assert!(script.api_calls.contains_key(&image));
+}
+
+#[test]
+fn set_value_summaries() {
+    let lines = [
+        r#"$5:"https\://a.com/a.js":x"#,
+        "!5",
+        r#"s1:{2,HTMLDivElement}:"onclick":handleClick"#,
+        r#"s2:{2,HTMLDivElement}:"innerHTML":"<p>Hello, world! This is a long paragraph.</p>""#,
+        r#"s3:{2,HTMLDivElement}:"innerHTML":"""#,
+        r#"s4:{2,HTMLDivElement}:"textContent":"a""#,
+        r#"s5:{2,HTMLDivElement}:"textContent":"b""#,
+        r#"s6:{3,Window}:"cdp":{663864,Object}"#,
+    ];
+    let mut aggregate = RecordAggregate {
+        summarize_set_values: true,
+        ..Default::default()
+    };
+    aggregate.add_lines(&lines).unwrap();
+    let script = aggregate.scripts.values().next().unwrap();
+    let set = |this: &str, attr: &str, value| ApiCall {
+        api_type: ApiType::Set,
+        this: this.into(),
+        attr: Some(attr.into()),
+        args: vec![],
+        value: Some(value),
+    };
+    let expected = [
+        set("HTMLDivElement", "onclick", ValueKind::Function),
+        set("HTMLDivElement", "innerHTML", ValueKind::String(None)),
+        set("HTMLDivElement", "textContent", ValueKind::String(None)),
+        set("Window", "cdp", ValueKind::Object(Some("Object".into()))),
+    ];
+    assert_eq!(script.api_calls.len(), expected.len());
+    for api_call in &expected {
+        assert!(script.api_calls.contains_key(api_call), "{api_call:?}");
+    }
+    // Different string assignments merge into one key.
+    assert_eq!(script.api_calls[&expected[1]].lines, [3, 4]);
+    assert_eq!(script.api_calls[&expected[2]].lines, [5, 6]);
+}
+
+#[test]
+fn source_positions() {
+    let script = ScriptAggregate {
+        source: "let s = \"\u{1f600}\";\nconsole.log(s);\ndocument.createElement(\"div\")".into(),
+        ..Default::default()
+    };
+    // The emoji takes two UTF-16 code units.
+    let offset = "let s = \"\u{1f600}\";\nconsole.".encode_utf16().count() as i32;
+    let expected = SourcePosition {
+        byte: "let s = \"\u{1f600}\";\nconsole.".len(),
+        line: 2,
+        column: 9,
+    };
+    assert_eq!(script.position(offset), Some(expected));
+    assert_eq!(script.snippet(offset, 3), Some("le.log"));
+    assert_eq!(script.snippet(offset, 100), Some("console.log(s);"));
+    assert_eq!(script.snippet(offset, 0), Some(""));
+    assert_eq!(script.position(0).unwrap().column, 1);
+    assert_eq!(script.position(-1), None);
+    assert_eq!(script.position(1000), None);
+}
+
+#[test]
+fn eval_trick_header() {
+    let aggregate = RecordAggregate::from_lines(&[
+        "~0x1",
+        "$5:\"https\\://a.com/a.js\"://10 effectiveLen\\x0avar 圏 = eval(String.raw`//4 effectiveLen\\x0aa();${迤(String.raw`//2 effectiveLen\\x0ab`)}`);",
+        "$6:5://4 effectiveLen\\x0aa();",
+        "$7:\"https\\://a.com/b.js\":b()",
+    ])
+    .unwrap();
+    let rewritten = &aggregate.scripts[&ScriptId { isolate: 1, id: 5 }];
+    assert!(rewritten.is_rewritten());
+    assert_eq!(rewritten.size(), 10);
+    let blocks = &rewritten.rewritten_blocks;
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].effective_len, 4);
+    assert!(rewritten.source[blocks[0].byte..].starts_with("//4 effectiveLen\n"));
+    assert_eq!(blocks[1].effective_len, 2);
+    assert!(rewritten.source[blocks[1].byte..].starts_with("//2 effectiveLen\n"));
+
+    let child = &aggregate.scripts[&ScriptId { isolate: 1, id: 6 }];
+    assert_eq!(child.effective_len, Some(4));
+    assert!(child.rewritten_blocks.is_empty());
+
+    let plain = &aggregate.scripts[&ScriptId { isolate: 1, id: 7 }];
+    assert!(!plain.is_rewritten());
+    assert_eq!(plain.size(), 3);
+}
+
+#[test]
+fn merge_mismatched_call_lines() {
+    let mut lines = CallLines::default();
+    let err = lines
+        .merge(&CallLines {
+            lines: vec![1, 2],
+            offsets: vec![10, 20],
+            ..Default::default()
+        })
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Merging CallLines with 2 lines, 2 offsets, and 0 phases"
+    );
+    assert!(lines.lines.is_empty());
+}
+
+#[test]
+fn rewritten_block_boundaries() {
+    let source = "//20 effectiveLen\n\
+        var 圏 = eval(String.raw`//4 effectiveLen\na(${'`'});${迤(String.raw`//2 effectiveLen\nb`)};\n`);\n\
+        var 圏 = eval(String.raw`//3 effectiveLen\nc()`);";
+    let blocks = RewrittenBlock::find_all(source);
+    let codes: Vec<_> = blocks
+        .iter()
+        .map(|block| &source[block.byte..block.end])
+        .collect();
+    assert_eq!(
+        codes,
+        [
+            "//4 effectiveLen\na(${'`'});${迤(String.raw`//2 effectiveLen\nb`)};\n",
+            "//2 effectiveLen\nb",
+            "//3 effectiveLen\nc()",
+        ]
+    );
+
+    let unterminated = "eval(String.raw`//1 effectiveLen\na";
+    let blocks = RewrittenBlock::find_all(unterminated);
+    assert_eq!(blocks[0].end, unterminated.len());
+}
@@ -0,0 +1,336 @@
+use super::*;
+
+/// A common functionality of JS that a script may be classified into,
+/// or a notable feature of the script.
+#[derive_everything]
+#[derive(Copy, Deserialize)]
+pub enum Sphere {
+    #[default]
+    FrontendProcessing,
+    DomElementGeneration,
+    UxEnhancement,
+    ExtensionalFeatures,
+    HasRequest,
+    QueriesElement,
+    UsesStorage,
+}
+
+impl Sphere {
+    pub const ALL: [Self; 7] = [
+        Self::FrontendProcessing,
+        Self::DomElementGeneration,
+        Self::UxEnhancement,
+        Self::ExtensionalFeatures,
+        Self::HasRequest,
+        Self::QueriesElement,
+        Self::UsesStorage,
+    ];
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// A set of [Sphere]s, e.g., those a script is classified into.
+#[derive_everything]
+#[derive(Copy)]
+pub struct SphereSet(u8);
+
+impl SphereSet {
+    pub fn insert(&mut self, sphere: Sphere) {
+        self.0 |= sphere.bit();
+    }
+
+    pub fn contains(&self, sphere: Sphere) -> bool {
+        self.0 & sphere.bit() != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// The spheres in the set, in the order of [Sphere::ALL].
+    pub fn iter(&self) -> impl Iterator<Item = Sphere> + '_ {
+        Sphere::ALL
+            .into_iter()
+            .filter(|sphere| self.contains(*sphere))
+    }
+}
+
+impl FromIterator<Sphere> for SphereSet {
+    fn from_iter<T: IntoIterator<Item = Sphere>>(iter: T) -> Self {
+        let mut set = Self::default();
+        for sphere in iter {
+            set.insert(sphere);
+        }
+        set
+    }
+}
+
+/// Classify `script` into spheres by heuristics on its API calls,
+/// using [SphereRules::default_rules].
+pub fn classify(script: &ScriptAggregate) -> SphereSet {
+    SphereRules::default_rules().classify(script)
+}
+
+/// The sphere `api_call` indicates, if any, using
+/// [SphereRules::default_rules].
+/// `lines` tells whether the call was made before any interaction.
+pub fn classify_api_call(api_call: &ApiCall, lines: &CallLines) -> Option<Sphere> {
+    SphereRules::default_rules().classify_api_call(api_call, lines)
+}
+
+/// Classify `script` like [classify], with the evidence for each sphere,
+/// using [SphereRules::default_rules].
+pub fn explain(script: &ScriptAggregate) -> Classification {
+    SphereRules::default_rules().explain(script)
+}
+
+/// Ordered rules mapping API calls to spheres, loadable from
+/// TOML or JSON rule files; see `classify/default_rules.toml`.
+#[pub_fields]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct SphereRules {
+    rules: Vec<SphereRule>,
+}
+
+static DEFAULT_RULES: LazyLock<SphereRules> = LazyLock::new(|| {
+    SphereRules::from_toml(include_str!("classify/default_rules.toml"))
+        .expect("Default sphere rules are valid")
+});
+
+impl SphereRules {
+    /// The rules shipped with this crate.
+    pub fn default_rules() -> &'static Self {
+        &DEFAULT_RULES
+    }
+
+    pub fn from_toml(rules: &str) -> Result<Self> {
+        toml::from_str(rules).context("Parsing TOML sphere rules")
+    }
+
+    pub fn from_json(rules: &str) -> Result<Self> {
+        serde_json::from_str(rules).context("Parsing JSON sphere rules")
+    }
+
+    /// Read a `.toml` or `.json` rule file.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let rules = fs::read_to_string(path).context("Reading sphere rule file")?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::from_toml(&rules),
+            Some("json") => Self::from_json(&rules),
+            _ => bail!("Sphere rule file {path:?} is neither TOML nor JSON"),
+        }
+    }
+
+    /// Classify `script` into the spheres its API calls indicate.
+    pub fn classify(&self, script: &ScriptAggregate) -> SphereSet {
+        script
+            .api_calls
+            .iter()
+            .filter_map(|(api_call, lines)| self.classify_api_call(api_call, lines))
+            .collect()
+    }
+
+    /// The sphere of the first rule `api_call` matches, if any.
+    pub fn classify_api_call(&self, api_call: &ApiCall, lines: &CallLines) -> Option<Sphere> {
+        self.matching_rule(api_call, lines)
+            .map(|(_, rule)| rule.sphere)
+    }
+
+    /// Classify `script` like [SphereRules::classify], recording
+    /// which API calls triggered each sphere, by which rule, and where.
+    pub fn explain(&self, script: &ScriptAggregate) -> Classification {
+        let mut classification = Classification::default();
+        for (api_call, lines) in &script.api_calls {
+            let Some((rule_index, rule)) = self.matching_rule(api_call, lines) else {
+                continue;
+            };
+            // Calls after interactions did not trigger such rules.
+            let n_call = match rule.only_before_interaction {
+                true => lines.n_must_not_interact(),
+                false => lines.len(),
+            } as usize;
+            classification.spheres.insert(rule.sphere);
+            classification
+                .evidence
+                .entry(rule.sphere)
+                .or_default()
+                .push(Evidence {
+                    api_call: api_call.clone(),
+                    rule_index,
+                    lines: lines.lines[..n_call].to_vec(),
+                    offsets: lines.offsets[..n_call].to_vec(),
+                });
+        }
+        for evidence in classification.evidence.values_mut() {
+            evidence.sort_unstable_by_key(|evidence| evidence.lines.first().copied());
+        }
+        classification
+    }
+
+    fn matching_rule(&self, api_call: &ApiCall, lines: &CallLines) -> Option<(usize, &SphereRule)> {
+        self.rules
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| rule.matches(api_call, lines))
+    }
+}
+
+/// Spheres of a script, with the evidence for each of them.
+#[pub_fields]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Classification {
+    spheres: SphereSet,
+    /// The API calls that triggered each sphere, ordered by first line.
+    evidence: BTreeMap<Sphere, Vec<Evidence>>,
+}
+
+/// An API call that triggered a sphere.
+#[pub_fields]
+#[derive_everything]
+pub struct Evidence {
+    api_call: ApiCall,
+    /// Index of the matching rule in [SphereRules::rules].
+    rule_index: usize,
+    /// Log line numbers of the triggering calls.
+    lines: Vec<u32>,
+    /// Character offsets of the triggering calls within the script source,
+    /// one per line in `lines`.
+    offsets: Vec<i32>,
+}
+
+impl std::fmt::Display for Classification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (sphere, evidence) in &self.evidence {
+            writeln!(f, "{sphere:?}:")?;
+            for Evidence {
+                api_call,
+                rule_index,
+                lines,
+                offsets,
+            } in evidence
+            {
+                writeln!(
+                    f,
+                    "  {:?} {}.{} by rule #{rule_index} at lines {lines:?}, offsets {offsets:?}",
+                    api_call.api_type,
+                    api_call.this,
+                    api_call.attr.as_deref().unwrap_or(""),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A rule that matches API calls satisfying all its conditions.
+/// Absent conditions match anything.
+#[pub_fields]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SphereRule {
+    sphere: Sphere,
+    api_type: Option<ApiType>,
+    this: Option<NamePattern>,
+    /// Never matches calls without `attr`, i.e., construction calls.
+    attr: Option<NamePattern>,
+    /// Only match calls made before any interaction
+    /// (see [CallLines::n_must_not_interact]).
+    #[serde(default)]
+    only_before_interaction: bool,
+}
+
+impl SphereRule {
+    pub fn matches(&self, api_call: &ApiCall, lines: &CallLines) -> bool {
+        self.api_type
+            .as_ref()
+            .is_none_or(|api_type| *api_type == api_call.api_type)
+            && self
+                .this
+                .as_ref()
+                .is_none_or(|this| this.matches(&api_call.this))
+            && self
+                .attr
+                .as_ref()
+                .is_none_or(|attr| api_call.attr.as_ref().is_some_and(|a| attr.matches(a)))
+            && (!self.only_before_interaction || lines.n_must_not_interact() > 0)
+    }
+}
+
+/// Pattern for `this` or `attr` names in [SphereRule]s.
+/// Unknown keys, e.g., typos, and tables with both `prefix` and `suffix`
+/// are rejected.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(try_from = "RawNamePattern")]
+pub enum NamePattern {
+    /// E.g., `"Location"`.
+    Exact(String),
+    /// E.g., `["HTMLInputElement", "HTMLTextAreaElement"]`.
+    AnyOf(Vec<String>),
+    /// E.g., `{ prefix = ["querySelector"] }`.
+    Prefix { prefix: Vec<String> },
+    /// E.g., `{ suffix = ["Event"] }`.
+    Suffix { suffix: Vec<String> },
+}
+
+/// The forms a [NamePattern] is written in.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawNamePattern {
+    Exact(String),
+    AnyOf(Vec<String>),
+    Affix(AffixPattern),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AffixPattern {
+    prefix: Option<Vec<String>>,
+    suffix: Option<Vec<String>>,
+}
+
+impl TryFrom<RawNamePattern> for NamePattern {
+    type Error = String;
+
+    fn try_from(raw: RawNamePattern) -> Result<Self, Self::Error> {
+        Ok(match raw {
+            RawNamePattern::Exact(exact) => Self::Exact(exact),
+            RawNamePattern::AnyOf(names) => Self::AnyOf(names),
+            RawNamePattern::Affix(AffixPattern {
+                prefix: Some(prefix),
+                suffix: None,
+            }) => Self::Prefix { prefix },
+            RawNamePattern::Affix(AffixPattern {
+                prefix: None,
+                suffix: Some(suffix),
+            }) => Self::Suffix { suffix },
+            RawNamePattern::Affix(_) => {
+                return Err("Name pattern needs exactly one of `prefix` and `suffix`".into())
+            }
+        })
+    }
+}
+
+impl NamePattern {
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::Exact(exact) => name == exact,
+            Self::AnyOf(names) => names.iter().any(|n| n == name),
+            Self::Prefix { prefix } => prefix.iter().any(|p| name.starts_with(p.as_str())),
+            Self::Suffix { suffix } => suffix.iter().any(|s| name.ends_with(s.as_str())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
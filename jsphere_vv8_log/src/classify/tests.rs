@@ -0,0 +1,151 @@
+use super::*;
+
+fn script_from_lines(lines: &[&str]) -> ScriptAggregate {
+    let aggregate = RecordAggregate::from_lines(lines).unwrap();
+    aggregate.scripts.into_values().next().unwrap()
+}
+
+#[test]
+fn classify_script() {
+    let script = script_from_lines(&[
+        r#"$5:"https\://a.com/a.js":x"#,
+        "!5",
+        r#"c1:%createElement:{1,HTMLDocument}:"div""#,
+        r#"g2:{2,MouseEvent}:"clientX""#,
+        r#"c3:%fetch:{3,Window}:"/api""#,
+        r#"c4:%getItem:{4,Storage}:"key""#,
+        r#"c5:%atob:{3,Window}:"""#,
+    ]);
+    let spheres = classify(&script);
+    let expected = [
+        Sphere::FrontendProcessing,
+        Sphere::DomElementGeneration,
+        Sphere::HasRequest,
+        Sphere::UsesStorage,
+    ];
+    assert_eq!(spheres.iter().collect::<Vec<_>>(), expected);
+    assert_eq!(spheres, expected.into_iter().collect());
+    assert_eq!(spheres.len(), 4);
+    assert!(!spheres.contains(Sphere::UxEnhancement));
+
+    assert!(classify(&ScriptAggregate::default()).is_empty());
+}
+
+#[test]
+fn custom_rules() {
+    let rules = SphereRules::from_json(
+        r#"{"rules": [
+            {"sphere": "UxEnhancement", "api_type": "Set", "this": {"suffix": ["Element"]},
+             "attr": ["hidden"], "only_before_interaction": true},
+            {"sphere": "HasRequest", "this": "WebSocket"}
+        ]}"#,
+    )
+    .unwrap();
+    assert_eq!(rules.rules.len(), 2);
+    let hidden = ApiCall {
+        api_type: ApiType::Set,
+        this: "HTMLDivElement".into(),
+        attr: Some("hidden".into()),
+        ..Default::default()
+    };
+    let before_interaction = CallLines {
+        lines: vec![1, 2],
+        offsets: vec![10, 20],
+        i_may_interact: Some(1),
+        ..Default::default()
+    };
+    let after_interaction = CallLines {
+        lines: vec![1, 2],
+        offsets: vec![10, 20],
+        i_may_interact: Some(0),
+        ..Default::default()
+    };
+    assert_eq!(
+        rules.classify_api_call(&hidden, &before_interaction),
+        Some(Sphere::UxEnhancement)
+    );
+    assert_eq!(rules.classify_api_call(&hidden, &after_interaction), None);
+    let web_socket = ApiCall {
+        api_type: ApiType::Construction,
+        this: "WebSocket".into(),
+        ..Default::default()
+    };
+    assert_eq!(
+        rules.classify_api_call(&web_socket, &after_interaction),
+        Some(Sphere::HasRequest)
+    );
+
+    let toml_rules = SphereRules::from_toml(
+        r#"
+        [[rules]]
+        sphere = "HasRequest"
+        this = "WebSocket"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(toml_rules.rules[0], rules.rules[1]);
+    assert!(SphereRules::from_toml("[[rules]]\nsphere = \"Unknown\"").is_err());
+
+    let suffix = SphereRules::from_toml(
+        r#"
+        [[rules]]
+        sphere = "FrontendProcessing"
+        this = { suffix = ["Event"] }
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        suffix.rules[0].this,
+        Some(NamePattern::Suffix {
+            suffix: vec!["Event".into()]
+        })
+    );
+    for bad_pattern in [
+        r#"{ prefix = ["on"], suffix = ["Event"] }"#,
+        r#"{ prefx = ["on"] }"#,
+        "{}",
+    ] {
+        let rules = format!("[[rules]]\nsphere = \"UxEnhancement\"\nattr = {bad_pattern}");
+        assert!(SphereRules::from_toml(&rules).is_err(), "{bad_pattern}");
+    }
+}
+
+#[test]
+fn explain_script() {
+    let mut aggregate = RecordAggregate::default();
+    let lines = [
+        r#"$5:"https\://a.com/a.js":x"#,
+        r#"$6:"":Gremlins horde"#,
+        "!5",
+        r#"c11:%createElement:{1,HTMLDocument}:"div""#,
+        r#"c22:%fetch:{3,Window}:"/api""#,
+        "!6",
+        "!5",
+        r#"c33:%createElement:{1,HTMLDocument}:"div""#,
+        r#"c44:%fetch:{3,Window}:"/api""#,
+    ];
+    aggregate.add_lines(&lines).unwrap();
+    let script = &aggregate.scripts[&ScriptId { isolate: 0, id: 5 }];
+    let classification = explain(script);
+    assert_eq!(classification.spheres, classify(script));
+
+    let dom = &classification.evidence[&Sphere::DomElementGeneration];
+    assert_eq!(dom.len(), 1);
+    assert_eq!(dom[0].api_call.attr.as_deref(), Some("createElement"));
+    // Only the call before the interaction triggered the sphere.
+    assert_eq!(
+        (&dom[0].lines[..], &dom[0].offsets[..]),
+        (&[3][..], &[11][..])
+    );
+    let rule = &SphereRules::default_rules().rules[dom[0].rule_index];
+    assert_eq!(rule.sphere, Sphere::DomElementGeneration);
+
+    let request = &classification.evidence[&Sphere::HasRequest];
+    assert_eq!(
+        (&request[0].lines[..], &request[0].offsets[..]),
+        (&[4, 8][..], &[22, 44][..])
+    );
+
+    let text = classification.to_string();
+    assert!(text.contains("Function HTMLDocument.createElement by rule #"));
+}
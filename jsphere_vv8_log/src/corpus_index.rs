@@ -0,0 +1,83 @@
+use super::*;
+
+/// Distinct scripts across a crawl corpus by [ContentHash], e.g.,
+/// to count a library that many sites include only once.
+#[pub_fields]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CorpusIndex {
+    scripts: HashMap<ContentHash, IndexedScript>,
+}
+
+impl CorpusIndex {
+    /// Index the page of every trial in `corpus`.
+    /// Trials that fail to read are logged and skipped.
+    pub fn build(corpus: &CrawlCorpus) -> Self {
+        let mut index = Self::default();
+        for (site, trial) in corpus.trials() {
+            match trial.page() {
+                Ok(page) => index.add_page(&site.name, trial.index, &page),
+                Err(err) => error!(?err, site = site.name, trial.index, "Reading page"),
+            }
+        }
+        index
+    }
+
+    /// Index the distinct scripts of `page`, the `trial`th trial of `site`.
+    pub fn add_page(&mut self, site: &str, trial: u32, page: &PageAggregate) {
+        for page_script in &page.scripts {
+            let script = self
+                .scripts
+                .entry(page_script.content_hash)
+                .or_insert_with(|| IndexedScript {
+                    size: page_script.size,
+                    ..Default::default()
+                });
+            if let Some(url) = &page_script.url {
+                script.urls.insert(url.clone());
+            }
+            script.trials.insert((site.into(), trial));
+            script.n_occurrence += page_script.occurrences.len();
+        }
+    }
+
+    /// Bytes of all distinct scripts, each counted once.
+    pub fn unique_bytes(&self) -> usize {
+        self.scripts.values().map(|script| script.size).sum()
+    }
+
+    /// Bytes of all scripts, counted once per time they ran,
+    /// see [IndexedScript::n_occurrence].
+    pub fn total_bytes(&self) -> usize {
+        self.scripts
+            .values()
+            .map(|script| script.size * script.n_occurrence)
+            .sum()
+    }
+}
+
+/// A distinct script in a [CorpusIndex].
+#[pub_fields]
+#[derive_everything]
+pub struct IndexedScript {
+    /// [ScriptAggregate::size].
+    size: usize,
+    /// URLs the script was served from.
+    urls: BTreeSet<String>,
+    /// Sites and trial indexes the script appeared in.
+    trials: BTreeSet<(String, u32)>,
+    /// Number of times the script ran across all trials, i.e., its
+    /// [PageScript::occurrences] summed over every URL it came from.
+    n_occurrence: usize,
+}
+
+impl IndexedScript {
+    /// Number of distinct sites the script appeared on.
+    pub fn n_site(&self) -> usize {
+        let mut sites: Vec<_> = self.trials.iter().map(|(site, _)| site).collect();
+        sites.dedup();
+        sites.len()
+    }
+}
+
+#[cfg(test)]
+mod tests;
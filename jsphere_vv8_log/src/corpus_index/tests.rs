@@ -0,0 +1,46 @@
+use super::*;
+
+fn page(lines: &[&str]) -> PageAggregate {
+    PageAggregate::from_aggregates(vec![RecordAggregate::from_lines(lines).unwrap()])
+}
+
+#[test]
+fn content_hash_is_stable() {
+    assert_eq!(
+        ContentHash::of("").to_string(),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+}
+
+#[test]
+fn dedup_across_sites() {
+    let jquery = r#"$5:"https\://cdn.test/jquery.js":jQuery()"#;
+    let a = page(&[
+        "~0x1",
+        jquery,
+        r#"$6:"https\://a.com/a.js":a()"#,
+        "~0x2",
+        jquery,
+    ]);
+    let b = page(&["~0x1", r#"$7:"https\://b.com/jquery.min.js":jQuery()"#]);
+    let mut index = CorpusIndex::default();
+    index.add_page("a.com", 0, &a);
+    index.add_page("a.com", 1, &a);
+    index.add_page("b.com", 0, &b);
+
+    assert_eq!(index.scripts.len(), 2);
+    let jquery = &index.scripts[&ContentHash::of("jQuery()")];
+    assert_eq!(jquery.size, 8);
+    assert_eq!(jquery.urls.len(), 2);
+    assert_eq!(jquery.trials.len(), 3);
+    assert_eq!(jquery.n_occurrence, 2 * 2 + 1);
+    assert_eq!(jquery.n_site(), 2);
+
+    assert_eq!(index.unique_bytes(), 8 + 3);
+    assert_eq!(index.total_bytes(), 8 * 5 + 3 * 2);
+
+    let mut one_page = CorpusIndex::default();
+    one_page.add_page("a.com", 0, &a);
+    assert_eq!(one_page.unique_bytes(), 8 + 3);
+    assert_eq!(one_page.total_bytes(), 8 * 2 + 3);
+}
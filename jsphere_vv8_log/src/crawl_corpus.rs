@@ -0,0 +1,178 @@
+use super::*;
+
+/// The crawler's output directory, e.g., `headless_browser/target/`,
+/// laid out as `<encodeURIComponent(site)>/<N>/` for each trial's VV8 logs,
+/// with `<N>.har` and `reachable<N>.json` next to them.
+#[pub_fields]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CrawlCorpus {
+    root: PathBuf,
+    /// Sites sorted by their directory names.
+    sites: Vec<Site>,
+}
+
+impl CrawlCorpus {
+    /// Discover the sites and their trials under `root`.
+    pub fn discover<P: AsRef<Path>>(root: P) -> Result<Self> {
+        let root = root.as_ref().to_owned();
+        let mut sites = Vec::new();
+        for entry in fs::read_dir(&root).context("Reading corpus directory")? {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    error!(?err, "Reading corpus directory entry");
+                    continue;
+                }
+            };
+            let dir = entry.path();
+            if !dir.is_dir() {
+                continue;
+            }
+            match Site::discover(dir) {
+                Ok(site) => sites.push(site),
+                Err(err) => error!(?err, "Discovering site"),
+            }
+        }
+        sites.sort_unstable_by(|a, b| a.dir.cmp(&b.dir));
+        Ok(Self { root, sites })
+    }
+
+    pub fn trials(&self) -> impl Iterator<Item = (&Site, &Trial)> {
+        self.sites
+            .iter()
+            .flat_map(|site| site.trials.iter().map(move |trial| (site, trial)))
+    }
+}
+
+/// A crawled site's directory.
+#[pub_fields]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Site {
+    /// The site as given to the crawler, decoded from the directory name,
+    /// e.g., `youtube.com`.
+    name: String,
+    dir: PathBuf,
+    /// Trials sorted by index.
+    trials: Vec<Trial>,
+}
+
+impl Site {
+    /// Discover the trials in the site directory `dir`, i.e.,
+    /// its subdirectories named by numbers.
+    pub fn discover(dir: PathBuf) -> Result<Self> {
+        let dir_name = dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .context("Site directory name")?;
+        let name = decode_uri_component(dir_name).unwrap_or_else(|| {
+            debug!(dir_name, "Site directory name is not percent-encoded UTF-8");
+            dir_name.into()
+        });
+        let mut trials = Vec::new();
+        for entry in fs::read_dir(&dir).context("Reading site directory")? {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(err) => {
+                    error!(?err, "Reading site directory entry");
+                    continue;
+                }
+            };
+            let index = match entry.file_name().to_str().map(str::parse) {
+                Some(Ok(index)) => index,
+                _ => continue,
+            };
+            let log_dir = entry.path();
+            if log_dir.is_dir() {
+                trials.push(Trial::new(&dir, index));
+            }
+        }
+        trials.sort_unstable_by_key(|trial| trial.index);
+        Ok(Self { name, dir, trials })
+    }
+}
+
+/// One visit to a site and the files the crawler wrote for it.
+#[pub_fields]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Trial {
+    /// `N` in the file names.
+    index: u32,
+    /// Directory of the VV8 logs, `<N>/`.
+    log_dir: PathBuf,
+    /// `<N>.har`, if written.
+    har: Option<HarFile>,
+    /// `reachable<N>.json`, if written.
+    reachable: Option<ReachableFile>,
+}
+
+impl Trial {
+    /// The trial `index` in the site directory `site_dir`,
+    /// with the optional files found on disk.
+    pub fn new(site_dir: &Path, index: u32) -> Self {
+        let har = site_dir.join(format!("{index}.har"));
+        let reachable = site_dir.join(format!("reachable{index}.json"));
+        Self {
+            index,
+            log_dir: site_dir.join(index.to_string()),
+            har: har.is_file().then_some(HarFile { path: har }),
+            reachable: reachable
+                .is_file()
+                .then_some(ReachableFile { path: reachable }),
+        }
+    }
+
+    pub fn read_logs(&self) -> Result<Vec<LogFile>> {
+        read_logs(&self.log_dir)
+    }
+
+    pub fn stream_logs(&self) -> Result<impl Iterator<Item = LogStream>> {
+        stream_logs(self.log_dir.clone())
+    }
+
+    /// [PageAggregate::read] the trial's logs.
+    pub fn page(&self) -> Result<PageAggregate> {
+        PageAggregate::read(&self.log_dir)
+    }
+}
+
+/// Handle to a trial's HTTP Archive, `<N>.har`.
+#[pub_fields]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HarFile {
+    path: PathBuf,
+}
+
+/// Handle to the pages a trial found reachable, `reachable<N>.json`.
+#[pub_fields]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReachableFile {
+    path: PathBuf,
+}
+
+/// Invert JS `encodeURIComponent`, which percent-encodes UTF-8 bytes.
+/// [None] if a `%` is not followed by two hex digits or
+/// the bytes are not UTF-8.
+pub fn decode_uri_component(encoded: &str) -> Option<String> {
+    if !encoded.contains('%') {
+        return Some(encoded.into());
+    }
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut rest = encoded.as_bytes();
+    while let Some((&byte, after)) = rest.split_first() {
+        match byte {
+            b'%' => {
+                let hex = std::str::from_utf8(after.get(..2)?).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                rest = &after[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = after;
+            }
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests;
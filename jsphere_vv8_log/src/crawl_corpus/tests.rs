@@ -0,0 +1,52 @@
+use super::*;
+
+#[test]
+fn decode_site_names() {
+    assert_eq!(decode_uri_component("youtube.com").unwrap(), "youtube.com");
+    assert_eq!(
+        decode_uri_component("https%3A%2F%2Fa.com%2F%E5%9C%8F").unwrap(),
+        "https://a.com/圏"
+    );
+    assert_eq!(decode_uri_component("a%2"), None);
+    assert_eq!(decode_uri_component("a%zz"), None);
+    assert_eq!(decode_uri_component("%FF"), None);
+}
+
+#[test]
+fn discover_sites_and_trials() {
+    let root = tempfile::tempdir().unwrap();
+    let site_dir = root.path().join("https%3A%2F%2Fa.com");
+    for dir in ["0", "1", "10", "user_data"] {
+        fs::create_dir_all(site_dir.join(dir)).unwrap();
+    }
+    fs::write(site_dir.join("0.har"), "{}").unwrap();
+    fs::write(site_dir.join("reachable0.json"), "{}").unwrap();
+    fs::write(site_dir.join("1.har"), "{}").unwrap();
+    fs::write(
+        site_dir.join("0/vv8-1726285073665-87-87-chrome.0.log"),
+        "~0x1\n$5:\"https\\://a.com/a.js\":a()\n",
+    )
+    .unwrap();
+    fs::create_dir_all(root.path().join("b.com")).unwrap();
+
+    let corpus = CrawlCorpus::discover(root.path()).unwrap();
+    let names: Vec<_> = corpus.sites.iter().map(|site| site.name.as_str()).collect();
+    assert_eq!(names, ["b.com", "https://a.com"]);
+    let site = &corpus.sites[1];
+    let indexes: Vec<_> = site.trials.iter().map(|trial| trial.index).collect();
+    assert_eq!(indexes, [0, 1, 10]);
+
+    let trial = &site.trials[0];
+    assert_eq!(trial.log_dir, site_dir.join("0"));
+    assert_eq!(trial.har.as_ref().unwrap().path, site_dir.join("0.har"));
+    assert_eq!(
+        trial.reachable.as_ref().unwrap().path,
+        site_dir.join("reachable0.json")
+    );
+    assert_eq!(trial.read_logs().unwrap().len(), 1);
+    assert_eq!(trial.page().unwrap().scripts.len(), 1);
+    assert!(site.trials[1].har.is_some());
+    assert!(site.trials[1].reachable.is_none());
+    assert!(site.trials[2].har.is_none());
+    assert_eq!(corpus.trials().count(), 3);
+}
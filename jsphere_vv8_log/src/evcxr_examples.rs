@@ -7,11 +7,9 @@ use crate as jsphere_vv8_log;
 :opt 3
 :dep rand
 :dep shame
-:dep lazy-regex
 :dep jsphere_vv8_log = { path = "jsphere_vv8_log" }
 // */
 use jsphere_vv8_log::*;
-use lazy_regex::regex_captures;
 use shame::prelude::*;
 use std::{
     collections::HashMap,
@@ -42,6 +40,22 @@ fn main() {
         println!("logs[{index}]: {info:?} | {n_records} records, {n_read_errs} read errors");
     }
 
+    // Stream large log files without loading all their records.
+    for stream in stream_logs("headless_browser/target/youtube.com/0").unwrap() {
+        println!("Streaming {:?}", stream.info());
+        let mut aggregate = RecordAggregate::default();
+        for result in stream {
+            match result {
+                Ok((line, record)) => {
+                    if let Err(err) = aggregate.add(line as u32, record) {
+                        println!("{line}: {err}");
+                    }
+                }
+                Err(read_err) => println!("{read_err:?}"),
+            }
+        }
+    }
+
     // Parse a log line.
     let log_line = r#"c7611:%eval:{823408,Window}:"() => window.__hordePromise__ !== undefined""#;
     let record = LogRecord::try_from(log_line).unwrap();
@@ -133,54 +147,88 @@ fn main() {
         file.flush().unwrap();
     }
 
-    println!("{}", &aggregate.scripts[&27].source);
+    let id = ScriptId {
+        isolate: aggregate.current_isolate,
+        id: 27,
+    };
+    println!("{}", &aggregate.scripts[&id].source);
+
+    //================================================================
+    // Aggregate each trial's page and keep only classifications that are
+    // stable across trials.
+    let corpus = CrawlCorpus::discover("headless_browser/target/").unwrap();
+    let site = corpus
+        .sites
+        .iter()
+        .find(|site| site.name == "youtube.com")
+        .unwrap();
+    let trials: Vec<_> = site
+        .trials
+        .iter()
+        .map(|trial| trial.page().unwrap())
+        .collect();
+    let consistency = TrialConsistency::new(&trials);
+    for script in consistency.scripts_in_all_trials() {
+        println!(
+            "{:?}: stable {:?}, flaky {:?}",
+            script.key,
+            script.stable_spheres().iter().collect::<Vec<_>>(),
+            script.flaky_spheres().iter().collect::<Vec<_>>(),
+        );
+    }
+
+    //================================================================
+    // Relate navigation API usage to whether pages reached subpages.
+    for (site, trial) in corpus.trials() {
+        let Some(reachable) = trial.read_reachable().unwrap() else {
+            continue;
+        };
+        let page = trial.page().unwrap();
+        let uses_navigation = page.aggregates.iter().any(|aggregate| {
+            aggregate
+                .scripts
+                .values()
+                .any(|script| script.api_calls.keys().any(ApiCall::is_navigation))
+        });
+        println!(
+            "{} {}: navigation API {uses_navigation}, {} of {} secondary pages same-site",
+            site.name,
+            trial.index,
+            reachable.same_site_subpages(&site.name).count(),
+            reachable.secondary_pages.len(),
+        );
+    }
 
     //================================================================
     // Shared functions for scanning logs.
-    fn for_each_log(mut callback: impl FnMut(LogFile, &str)) {
-        for dir_entry_result in fs::read_dir("headless_browser/target/").unwrap() {
-            let dir_entry = dir_entry_result.unwrap();
-            let subdomain_dir = dir_entry.path();
-            let subdomain = subdomain_dir.file_name().unwrap().to_str().unwrap();
-            for trial in 0..5 {
-                let trial_dir = subdomain_dir.join(trial.to_string());
-                if trial_dir.exists() && trial_dir.is_dir() {
-                    println!("Scanning `{}`", trial_dir.to_string_lossy());
-                    let logs = read_logs(&trial_dir).unwrap();
-                    for log in logs {
-                        callback(log, subdomain);
-                    }
-                }
+    fn for_each_log(mut callback: impl FnMut(StitchedLog, &str)) {
+        let corpus = CrawlCorpus::discover("headless_browser/target/").unwrap();
+        for (site, trial) in corpus.trials() {
+            println!("Scanning `{}`", trial.log_dir.to_string_lossy());
+            for log in StitchedLog::stitch(trial.read_logs().unwrap()) {
+                callback(log, &site.name);
             }
         }
     }
     fn aggregate_records(
-        log: LogFile,
+        log: StitchedLog,
         unknown_id_logs: &mut Vec<(String, usize)>,
     ) -> RecordAggregate {
-        let LogFileInfo {
-            timestamp,
-            pid,
-            tid,
-            thread_name,
-        } = &log.info;
-        let info = format!(
-            "[{timestamp} {pid} {tid} {thread_name}] {} records {} read errors",
-            log.records.len(),
-            log.read_errs.len()
-        );
-        if !log.read_errs.is_empty() {
+        let StitchedLog { pid, tid, parts } = &log;
+        let n_records: usize = parts.iter().map(|part| part.records.len()).sum();
+        let n_read_errs: usize = parts.iter().map(|part| part.read_errs.len()).sum();
+        let thread_names: Vec<_> = parts.iter().map(|part| &part.info.thread_name).collect();
+        let info =
+            format!("[{pid} {tid} {thread_names:?}] {n_records} records {n_read_errs} read errors");
+        if n_read_errs > 0 {
             println!("{info}");
         }
         let mut has_unknown_id = false;
         let mut aggregate = RecordAggregate::default();
-        for (line, record) in log.records {
+        for (line, record) in log.into_records() {
             if let Err(err) = aggregate.add(line as u32, record) {
                 let err_str = format!("{err}");
                 if err_str.ends_with("Unknown execution context script ID") {
-                    // TODO: This is caused by the `chrome.1` and
-                    // `chrome.2` threads being continuations of
-                    // previous logs.
                     if !has_unknown_id {
                         unknown_id_logs.push((info.clone(), line));
                         has_unknown_id = true;
@@ -194,7 +242,7 @@ fn main() {
         aggregate
     }
     fn for_each_filtered_script(
-        mut callback: impl FnMut(i32, ScriptAggregate, &str),
+        mut callback: impl FnMut(ScriptId, ScriptAggregate, &str),
         unknown_id_logs: &mut Vec<(String, usize)>,
     ) {
         for_each_log(|mut log, subdomain| {
@@ -202,7 +250,7 @@ fn main() {
             for (id, script) in aggregate.scripts {
                 // Filter out injected scripts.
                 if matches!(script.injection_type, ScriptInjectionType::Not)
-                    && script.source != "window.history.back()"
+                    && script.source != CRAWLER_BACK_NAVIGATION_SOURCE
                 {
                     callback(id, script, subdomain);
                 }
@@ -263,6 +311,8 @@ fn main() {
                 api_type,
                 this,
                 attr,
+                args: _,
+                value: _,
             },
             CallCounts {
                 appear_in,
@@ -297,7 +347,7 @@ fn main() {
     // Classify each script by heuristics.
     #[derive(Clone, Debug, Default)]
     struct ScriptFeatures {
-        id: i32,
+        id: ScriptId,
         name: Option<String>,
         subdomain: String,
         size: usize,
@@ -312,143 +362,35 @@ fn main() {
         uses_storage: bool,
     }
     fn script_aggregate2feature(
-        id: i32,
+        id: ScriptId,
         subdomain: String,
         script: ScriptAggregate,
     ) -> ScriptFeatures {
         let ScriptAggregate {
-            line,
             name,
-            source,
-            injection_type: _,
             api_calls,
             n_filtered_call,
-        } = script;
-        // NOTE: We have the `effectiveLen` if the script is rewritten.
-        let (size, rewritten) = match regex_captures!(r"^//(\d+) effectiveLen", &source)
-            .and_then(|(_, len)| len.parse::<usize>().ok())
-        {
-            Some(len) => (len, true),
-            None => (source.len(), false),
-        };
+            ..
+        } = &script;
         let mut features = ScriptFeatures {
             id,
             subdomain,
-            size,
-            rewritten,
+            size: script.size(),
+            rewritten: script.is_rewritten(),
             total_call: api_calls.len() as u32 + n_filtered_call,
             ..ScriptFeatures::default()
         };
         if let ScriptName::Url(name) = name {
-            features.name = Some(name);
-        }
-        for (
-            ApiCall {
-                api_type,
-                this,
-                attr,
-            },
-            lines,
-        ) in api_calls
-        {
-            match (api_type, (this.as_str(), attr.as_deref())) {
-                // Frontend processing.
-                (
-                    ApiType::Get,
-                    (
-                        this,
-                        Some(
-                            "state" | "keyCode" | "pointerType" | "which" | "bubbles" | "clientY"
-                            | "target" | "key" | "charCode" | "clientX" | "pointerId"
-                            | "currentTarget" | "isTrusted" | "propertyName" | "preventDefault"
-                            | "offsetY" | "cancelable" | "changedTouches" | "composed" | "screenX"
-                            | "button" | "composedPath" | "metaKey" | "pageY" | "ctrlKey"
-                            | "touches" | "detail" | "shiftKey" | "type" | "offsetX" | "pageX"
-                            | "eventPhase" | "timeStamp" | "screenY" | "altKey" | "data"
-                            | "relatedTarget" | "defaultPrevented",
-                        ),
-                    ),
-                ) if this.ends_with("Event") => features.sure_frontend_processing = true,
-                (
-                    ApiType::Get,
-                    ("Location", Some("pathname" | "hash" | "href" | "hostname" | "search"))
-                    | ("HTMLInputElement" | "HTMLTextAreaElement", Some("value" | "checked")),
-                )
-                | (ApiType::Function, (_, Some("addEventListener")))
-                | (
-                    ApiType::Set,
-                    (_, Some("textContent"))
-                    | ("URLSearchParams" | "DOMRect" | "DOMRectReadOnly", Some(_)),
-                ) => features.sure_frontend_processing = true,
-
-                // DOM element generation.
-                (
-                    ApiType::Function,
-                    (
-                        _,
-                        Some(
-                            "createElement" | "createElementNS" | "createTextNode" | "appendChild"
-                            | "insertBefore",
-                        ),
-                    )
-                    | ("CSSStyleDeclaration", Some("setProperty")),
-                )
-                | (ApiType::Set, ("CSSStyleDeclaration", _) | (_, Some("style")))
-                    if lines.n_must_not_interact() > 0 =>
-                {
-                    features.sure_dom_element_generation = true
-                }
-
-                // UX enhancement.
-                (
-                    ApiType::Function,
-                    (
-                        _,
-                        Some(
-                            "removeAttribute"
-                            | "matchMedia"
-                            | "removeChild"
-                            | "requestAnimationFrame"
-                            | "cancelAnimationFrame",
-                        ),
-                    )
-                    | ("FontFaceSet", Some("load"))
-                    | ("MediaQueryList", Some("matches")),
-                )
-                | (ApiType::Set, (_, Some("hidden" | "disabled"))) => {
-                    features.sure_ux_enhancement = true
-                }
-
-                // Extensional features.
-                (
-                    ApiType::Function,
-                    ("Performance" | "PerformanceTiming" | "PerformanceResourceTiming", _)
-                    | ("Navigator", Some("sendBeacon")),
-                    // TODO: This list can be extended much more.
-                ) => features.sure_extensional_featuers = true,
-
-                // Requests.
-                (ApiType::Function, ("XMLHttpRequest", _) | ("Window", Some("fetch"))) => {
-                    features.has_request = true
-                }
-
-                // Queries element.
-                (ApiType::Get, (_, Some(attr)))
-                    if attr.starts_with("querySelector")
-                        || attr.starts_with("getElementBy")
-                        || attr.starts_with("getElementsBy") =>
-                {
-                    features.queries_element = true
-                }
-
-                // Uses storage.
-                (ApiType::Function, ("Storage", _) | ("HTMLDocument", Some("cookie"))) => {
-                    features.uses_storage = true
-                }
-
-                _ => {}
-            }
+            features.name = Some(name.clone());
         }
+        let spheres = classify(&script);
+        features.sure_frontend_processing = spheres.contains(Sphere::FrontendProcessing);
+        features.sure_dom_element_generation = spheres.contains(Sphere::DomElementGeneration);
+        features.sure_ux_enhancement = spheres.contains(Sphere::UxEnhancement);
+        features.sure_extensional_featuers = spheres.contains(Sphere::ExtensionalFeatures);
+        features.has_request = spheres.contains(Sphere::HasRequest);
+        features.queries_element = spheres.contains(Sphere::QueriesElement);
+        features.uses_storage = spheres.contains(Sphere::UsesStorage);
         features
     }
 
@@ -509,47 +451,18 @@ fn main() {
 
     //================================================================
     // Randomly validate script classification.
-    fn script_name(name: &ScriptName, aggregate: &RecordAggregate, mut is_child: bool) -> String {
-        let inner = match name {
-            ScriptName::Empty => "<no name>".into(),
-            ScriptName::Url(url) => url.clone(),
-            ScriptName::Eval { parent_script_id } => {
-                let parent = match aggregate.scripts.get(parent_script_id) {
-                    Some(script) => script,
-                    None => return "".into(),
-                };
-                is_child = false;
-                script_name(&parent.name, aggregate, true)
-            }
-        };
-        match is_child {
-            true => format!("child of {}", inner),
-            false => inner,
-        }
-    }
-    let mut trial_dirs = Vec::<PathBuf>::with_capacity(4096);
+    let corpus = CrawlCorpus::discover("headless_browser/target/").unwrap();
+    let trials: Vec<_> = corpus.trials().collect();
     let mut unknown_id_logs = Vec::<(String, usize)>::new();
-    for dir_entry_result in fs::read_dir("headless_browser/target/").unwrap() {
-        let dir_entry = dir_entry_result.unwrap();
-        let subdomain_dir = dir_entry.path();
-        let subdomain = subdomain_dir.file_name().unwrap().to_str().unwrap();
-        for trial in 0..5 {
-            let trial_dir = subdomain_dir.join(trial.to_string());
-            if trial_dir.exists() && trial_dir.is_dir() {
-                trial_dirs.push(trial_dir);
-            }
-        }
-    }
-    trial_dirs.shrink_to_fit();
     let mut stdin = std::io::stdin();
     let mut input_buf = String::new();
     let mut record = Vec::<(RecordAggregate, ScriptFeatures, bool)>::with_capacity(100);
     let mut n_trial = 0;
     while n_trial < 100 {
-        let index = rand::random::<usize>() % trial_dirs.len();
-        let trial_dir = &trial_dirs[index];
-        println!("Scanning `{}`", trial_dir.to_string_lossy());
-        let logs = read_logs(trial_dir).unwrap();
+        let index = rand::random::<usize>() % trials.len();
+        let (site, trial) = trials[index];
+        println!("Scanning `{}`", trial.log_dir.to_string_lossy());
+        let logs = StitchedLog::stitch(trial.read_logs().unwrap());
         if logs.is_empty() {
             continue;
         }
@@ -562,14 +475,37 @@ fn main() {
         let index = rand::random::<usize>() % aggregate.scripts.len();
         let (id, script) = aggregate.scripts.iter().nth(index).unwrap();
         if !matches!(script.injection_type, ScriptInjectionType::Not)
-            || script.source == "window.history.back()"
+            || script.source == CRAWLER_BACK_NAVIGATION_SOURCE
         {
             break;
         }
-        let subdomain = trial_dir.file_name().unwrap().to_str().unwrap();
-        let features = script_aggregate2feature(*id, subdomain.into(), script.clone());
-        let name = script_name(&script.name, &aggregate, false);
-        println!("\n\n{source}\n{features:?} {name}", source = script.source);
+        let features = script_aggregate2feature(*id, site.name.clone(), script.clone());
+        let tree = ScriptTree::new(&aggregate);
+        let root_id = tree.root_of(*id);
+        let name = match &aggregate.scripts[&root_id].name {
+            ScriptName::Url(url) => url.clone(),
+            _ => "<no name>".into(),
+        };
+        let name = match root_id == *id {
+            true => name,
+            false => format!("child of {name}"),
+        };
+        let library = tree.library(*id);
+        println!(
+            "\n\n{source}\n{features:?} {name} {library:?}",
+            source = script.source
+        );
+        let classification = explain(script);
+        println!("{classification}");
+        for evidence in classification.evidence.values().flatten() {
+            if let Some(&offset) = evidence.offsets.first() {
+                if let (Some(position), Some(snippet)) =
+                    (script.position(offset), script.snippet(offset, 40))
+                {
+                    println!("{position:?}: {snippet}");
+                }
+            }
+        }
         // Prompt for correctness.
         loop {
             println!("Is the classification correct? [Y/n]");
@@ -0,0 +1,147 @@
+use super::*;
+
+/// A recognized library or third-party tag.
+#[pub_fields]
+#[derive_everything]
+pub struct Library {
+    /// E.g., `jQuery`.
+    name: String,
+    kind: LibraryKind,
+    /// E.g., `3.7.1`, if recognizable.
+    version: Option<String>,
+}
+
+/// What a [Library] is for.
+#[derive_everything]
+#[derive(Copy, Deserialize)]
+pub enum LibraryKind {
+    /// General-purpose libraries and frameworks, e.g., jQuery and React.
+    Library,
+    Analytics,
+    Advertising,
+    TagManager,
+    /// Social media widgets and SDKs.
+    Social,
+    #[default]
+    Other,
+}
+
+/// Fingerprint `script` with [LibraryRules::default_rules].
+pub fn fingerprint(script: &ScriptAggregate) -> Option<Library> {
+    LibraryRules::default_rules().fingerprint(script)
+}
+
+/// Ordered rules recognizing libraries by script URLs and source signatures,
+/// loadable from TOML or JSON rule files;
+/// see `fingerprint/default_libraries.toml`.
+#[pub_fields]
+#[derive(Clone, Debug, Deserialize)]
+pub struct LibraryRules {
+    /// Bytes at the start of the source to search for signatures.
+    source_prefix_len: usize,
+    libraries: Vec<LibraryRule>,
+}
+
+static DEFAULT_LIBRARIES: LazyLock<LibraryRules> = LazyLock::new(|| {
+    LibraryRules::from_toml(include_str!("fingerprint/default_libraries.toml"))
+        .expect("Default library rules are valid")
+});
+
+impl LibraryRules {
+    /// The rules shipped with this crate.
+    pub fn default_rules() -> &'static Self {
+        &DEFAULT_LIBRARIES
+    }
+
+    pub fn from_toml(rules: &str) -> Result<Self> {
+        toml::from_str(rules).context("Parsing TOML library rules")
+    }
+
+    pub fn from_json(rules: &str) -> Result<Self> {
+        serde_json::from_str(rules).context("Parsing JSON library rules")
+    }
+
+    /// Read a `.toml` or `.json` rule file.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let rules = fs::read_to_string(path).context("Reading library rule file")?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Self::from_toml(&rules),
+            Some("json") => Self::from_json(&rules),
+            _ => bail!("Library rule file {path:?} is neither TOML nor JSON"),
+        }
+    }
+
+    /// The library of the first rule `script` matches, if any.
+    pub fn fingerprint(&self, script: &ScriptAggregate) -> Option<Library> {
+        let url = match &script.name {
+            ScriptName::Url(url) => Some(url.as_str()),
+            _ => None,
+        };
+        let mut end = script.source.len().min(self.source_prefix_len);
+        while !script.source.is_char_boundary(end) {
+            end -= 1;
+        }
+        let source = &script.source[..end];
+        self.libraries
+            .iter()
+            .find_map(|rule| rule.fingerprint(url, source))
+    }
+}
+
+/// A rule that recognizes a library if any of its patterns match.
+#[pub_fields]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LibraryRule {
+    name: String,
+    kind: LibraryKind,
+    /// Searched in [ScriptName::Url].
+    url: Option<Pattern>,
+    /// Searched in the start of the source.
+    source: Option<Pattern>,
+}
+
+impl LibraryRule {
+    /// The library if `url` or `source` matches, with the version
+    /// captured by the pattern, preferring `source`'s.
+    pub fn fingerprint(&self, url: Option<&str>, source: &str) -> Option<Library> {
+        let url_match = self
+            .url
+            .as_ref()
+            .zip(url)
+            .and_then(|(pattern, url)| pattern.0.captures(url));
+        let source_match = self
+            .source
+            .as_ref()
+            .and_then(|pattern| pattern.0.captures(source));
+        if url_match.is_none() && source_match.is_none() {
+            return None;
+        }
+        let version = [source_match, url_match]
+            .into_iter()
+            .flatten()
+            .find_map(|captures| Some(captures.get(1)?.as_str().to_owned()));
+        Some(Library {
+            name: self.name.clone(),
+            kind: self.kind,
+            version,
+        })
+    }
+}
+
+/// A regex in [LibraryRule]s.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Pattern(pub Regex);
+
+impl TryFrom<String> for Pattern {
+    type Error = lazy_regex::regex::Error;
+
+    fn try_from(pattern: String) -> Result<Self, Self::Error> {
+        Regex::new(&pattern).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,124 @@
+use super::*;
+
+fn script(url: Option<&str>, source: &str) -> ScriptAggregate {
+    ScriptAggregate {
+        name: url.map_or(ScriptName::Empty, |url| ScriptName::Url(url.into())),
+        source: source.into(),
+        ..Default::default()
+    }
+}
+
+fn library(name: &str, kind: LibraryKind, version: Option<&str>) -> Option<Library> {
+    Some(Library {
+        name: name.into(),
+        kind,
+        version: version.map(Into::into),
+    })
+}
+
+#[test]
+fn default_libraries() {
+    use LibraryKind::*;
+    assert_eq!(
+        fingerprint(&script(
+            Some("https://a.com/js/jquery-3.6.0.min.js"),
+            "/*! jQuery v3.7.1 | (c) OpenJS Foundation */"
+        )),
+        library("jQuery", Library, Some("3.7.1"))
+    );
+    assert_eq!(
+        fingerprint(&script(
+            Some("https://a.com/js/jquery-3.6.0.min.js"),
+            "minified"
+        )),
+        library("jQuery", Library, Some("3.6.0"))
+    );
+    assert_eq!(
+        fingerprint(&script(
+            Some("https://a.com/js/jquery-ui.min.js"),
+            "/*! jQuery UI - v1.13.2 - 2022-07-14"
+        )),
+        library("jQuery UI", Library, Some("1.13.2"))
+    );
+    assert_eq!(
+        fingerprint(&script(
+            Some("https://a.com/bundle.js"),
+            "/** @license React v17.0.2\n * react-dom.production.min.js"
+        )),
+        library("React", Library, Some("17.0.2"))
+    );
+    assert_eq!(
+        fingerprint(&script(
+            Some("https://www.googletagmanager.com/gtm.js?id=GTM-ABC"),
+            "var data = {}"
+        )),
+        library("Google Tag Manager", TagManager, None)
+    );
+    assert_eq!(
+        fingerprint(&script(
+            Some("https://connect.facebook.net/en_US/fbevents.js"),
+            ""
+        )),
+        library("Meta Pixel", Advertising, None)
+    );
+    assert_eq!(
+        fingerprint(&script(Some("https://a.com/app.js"), "jQuery(init)")),
+        None
+    );
+    assert_eq!(fingerprint(&script(None, "function app() {}")), None);
+}
+
+#[test]
+fn jquery_migrate_banners() {
+    let cdn_url = Some("https://cdn.example.com/libs/compat.min.js");
+    for (banner, version) in [
+        (
+            "/*! jQuery Migrate v1.4.1 | (c) jQuery Foundation and other contributors | jquery.org/license */",
+            "1.4.1",
+        ),
+        ("/*!\n * jQuery Migrate - v1.2.1 - 2013-05-08", "1.2.1"),
+        (
+            "/*! jQuery Migrate v3.4.1 | (c) OpenJS Foundation and other contributors | jquery.org/license */",
+            "3.4.1",
+        ),
+    ] {
+        assert_eq!(
+            fingerprint(&script(cdn_url, banner)),
+            library("jQuery Migrate", LibraryKind::Library, Some(version)),
+            "{banner}"
+        );
+    }
+}
+
+#[test]
+fn source_signature_only_in_prefix() {
+    let rules = LibraryRules::from_toml(
+        r#"
+source_prefix_len = 8
+[[libraries]]
+name = "Lib"
+kind = "Library"
+source = 'Lib v(\d+)'
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        rules.fingerprint(&script(None, "Lib v2")),
+        library("Lib", LibraryKind::Library, Some("2"))
+    );
+    assert_eq!(rules.fingerprint(&script(None, "/* app */ Lib v2")), None);
+    // Do not split multi-byte characters.
+    assert_eq!(rules.fingerprint(&script(None, "圏圏圏Lib v2")), None);
+
+    let err = LibraryRules::from_toml(
+        r#"
+source_prefix_len = 8
+[[libraries]]
+name = "Lib"
+kind = "Library"
+source = '('
+"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().starts_with("Parsing TOML library rules"));
+}
@@ -0,0 +1,208 @@
+use super::*;
+
+/// The HTTP Archive (HAR) the crawler records with Playwright per trial,
+/// keeping only the fields we use.
+/// See <http://www.softwareishard.com/blog/har-12-spec/>.
+#[derive_float_everything]
+#[derive(Deserialize)]
+#[pub_fields]
+pub struct Har {
+    /// Requests in the order the browser made them.
+    entries: Vec<HarEntry>,
+}
+
+/// The top-level `{"log": …}` wrapper of a HAR file.
+#[derive(Deserialize)]
+struct HarRoot {
+    log: Har,
+}
+
+impl Har {
+    pub fn from_json(har: &str) -> Result<Self> {
+        let root: HarRoot = serde_json::from_str(har).context("Parsing HAR")?;
+        Ok(root.log)
+    }
+
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path).context("Opening HAR file")?;
+        let root: HarRoot = serde_json::from_reader(BufReader::new(file)).context("Parsing HAR")?;
+        Ok(root.log)
+    }
+
+    /// The response that served `url`, preferring the last successful one,
+    /// e.g., over redirects or a failed first try.
+    pub fn response_for(&self, url: &str) -> Option<&HarEntry> {
+        let mut matches = self
+            .entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.request.url == url);
+        let first = matches.next()?;
+        match first.response.is_success() {
+            true => Some(first),
+            false => matches
+                .find(|entry| entry.response.is_success())
+                .or(Some(first)),
+        }
+    }
+
+    /// Each [ScriptName::Url] script in `aggregate` that
+    /// has a response in this HAR, paired with the response.
+    pub fn script_responses<'a>(
+        &'a self,
+        aggregate: &'a RecordAggregate,
+    ) -> HashMap<ScriptId, &'a HarEntry> {
+        aggregate
+            .scripts
+            .iter()
+            .filter_map(|(&id, script)| match &script.name {
+                ScriptName::Url(url) => Some((id, self.response_for(url)?)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl HarFile {
+    pub fn read(&self) -> Result<Har> {
+        Har::read(&self.path)
+    }
+}
+
+/// One request and its response.
+#[derive_float_everything]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[pub_fields]
+pub struct HarEntry {
+    /// ISO 8601 start time, e.g., `2024-09-14T03:37:53.665Z`.
+    started_date_time: String,
+    /// Total elapsed milliseconds, the sum of `timings`.
+    time: f64,
+    request: HarRequest,
+    response: HarResponse,
+    #[serde(default)]
+    timings: HarTimings,
+    /// Chrome's record of what started the request.
+    /// Playwright does not write it, so it is mostly [None].
+    #[serde(default, rename = "_initiator")]
+    initiator: Option<HarInitiator>,
+}
+
+impl HarEntry {
+    /// Bytes sent over the wire for the response, including headers,
+    /// if known.
+    pub fn transfer_size(&self) -> Option<u64> {
+        let response = &self.response;
+        match response.transfer_size {
+            Some(size) if size >= 0 => Some(size as u64),
+            _ if response.headers_size >= 0 && response.body_size >= 0 => {
+                Some((response.headers_size + response.body_size) as u64)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive_float_everything]
+#[derive(Deserialize)]
+#[pub_fields]
+pub struct HarRequest {
+    method: String,
+    url: String,
+}
+
+#[derive_float_everything]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[pub_fields]
+pub struct HarResponse {
+    status: i64,
+    #[serde(default)]
+    headers: Vec<HarHeader>,
+    content: HarContent,
+    /// Bytes of the headers, or -1 if unknown.
+    #[serde(default = "unknown_size")]
+    headers_size: i64,
+    /// Bytes of the received, possibly compressed body, or -1 if unknown.
+    #[serde(default = "unknown_size")]
+    body_size: i64,
+    /// Chrome's and Playwright's total bytes received, or -1 if unknown.
+    #[serde(default, rename = "_transferSize")]
+    transfer_size: Option<i64>,
+}
+
+fn unknown_size() -> i64 {
+    -1
+}
+
+impl HarResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// The value of the first header named `name`, case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|header| header.name.eq_ignore_ascii_case(name))
+            .map(|header| header.value.as_str())
+    }
+
+    /// The `Content-Encoding`, e.g., `gzip` or `br`, if compressed.
+    pub fn content_encoding(&self) -> Option<&str> {
+        self.header("content-encoding")
+            .filter(|encoding| !encoding.eq_ignore_ascii_case("identity"))
+    }
+}
+
+#[derive_float_everything]
+#[derive(Deserialize)]
+#[pub_fields]
+pub struct HarHeader {
+    name: String,
+    value: String,
+}
+
+#[derive_float_everything]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[pub_fields]
+pub struct HarContent {
+    /// Bytes of the decoded body.
+    size: i64,
+    /// Bytes saved by compression, if known.
+    #[serde(default)]
+    compression: Option<i64>,
+    #[serde(default)]
+    mime_type: Option<String>,
+}
+
+/// Milliseconds spent in each stage of the request, -1 if not applicable.
+#[derive_float_everything]
+#[derive(Deserialize)]
+#[serde(default)]
+#[pub_fields]
+pub struct HarTimings {
+    blocked: f64,
+    dns: f64,
+    connect: f64,
+    ssl: f64,
+    send: f64,
+    wait: f64,
+    receive: f64,
+}
+
+#[derive_float_everything]
+#[derive(Deserialize)]
+#[pub_fields]
+pub struct HarInitiator {
+    /// E.g., `parser`, `script`, or `other`.
+    #[serde(rename = "type")]
+    initiator_type: String,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,108 @@
+use super::*;
+
+const HAR: &str = r#"{
+  "log": {
+    "version": "1.2",
+    "creator": { "name": "Playwright", "version": "1.47.0" },
+    "entries": [
+      {
+        "startedDateTime": "2024-09-14T03:37:53.665Z",
+        "time": 50.5,
+        "request": { "method": "GET", "url": "https://a.com/a.js", "headers": [] },
+        "response": {
+          "status": 503,
+          "headers": [],
+          "content": { "size": 0 },
+          "headersSize": 100,
+          "bodySize": 0,
+          "_transferSize": 100
+        },
+        "cache": {},
+        "timings": { "send": 1, "wait": 49, "receive": 0.5 }
+      },
+      {
+        "startedDateTime": "2024-09-14T03:37:54.000Z",
+        "time": 20,
+        "request": { "method": "GET", "url": "https://a.com/a.js", "headers": [] },
+        "response": {
+          "status": 200,
+          "headers": [{ "name": "Content-Encoding", "value": "gzip" }],
+          "content": { "size": 10, "mimeType": "text/javascript", "compression": 2 },
+          "headersSize": 120,
+          "bodySize": 8,
+          "_transferSize": -1
+        },
+        "timings": { "dns": -1, "wait": 15, "receive": 5 },
+        "_initiator": { "type": "parser", "url": "https://a.com/" }
+      },
+      {
+        "startedDateTime": "2024-09-14T03:37:54.100Z",
+        "time": 5,
+        "request": { "method": "GET", "url": "https://a.com/b.js", "headers": [] },
+        "response": {
+          "status": 200,
+          "headers": [],
+          "content": { "size": 3 },
+          "headersSize": -1,
+          "bodySize": 3,
+          "_transferSize": 50
+        },
+        "timings": {}
+      }
+    ]
+  }
+}"#;
+
+#[test]
+fn parse_har() {
+    let har = Har::from_json(HAR).unwrap();
+    assert_eq!(har.entries.len(), 3);
+
+    let a = har.response_for("https://a.com/a.js").unwrap();
+    assert_eq!(a.started_date_time, "2024-09-14T03:37:54.000Z");
+    assert_eq!(a.response.status, 200);
+    assert_eq!(a.response.content_encoding(), Some("gzip"));
+    assert_eq!(a.response.content.compression, Some(2));
+    assert_eq!(a.transfer_size(), Some(128));
+    assert_eq!(a.timings.wait, 15.0);
+    let initiator = a.initiator.as_ref().unwrap();
+    assert_eq!(initiator.initiator_type, "parser");
+
+    let b = har.response_for("https://a.com/b.js").unwrap();
+    assert_eq!(b.transfer_size(), Some(50));
+    assert_eq!(b.response.content_encoding(), None);
+    assert_eq!(b.initiator, None);
+    assert!(har.response_for("https://a.com/c.js").is_none());
+}
+
+#[test]
+fn join_scripts_with_responses() {
+    let har = Har::from_json(HAR).unwrap();
+    let mut aggregate = RecordAggregate::default();
+    let lines = [
+        "~0x1",
+        r#"$5:"https\://a.com/a.js":fetch("/")"#,
+        r#"$6:"https\://a.com/b.js":b()"#,
+        r#"$7:"":inline()"#,
+        r#"$8:6:localStorage"#,
+        "!5",
+        r#"c1:%fetch:{1,Window}:"/""#,
+        "!8",
+        r#"c3:%getItem:{2,Storage}:"k""#,
+    ];
+    aggregate.add_lines(&lines).unwrap();
+    let responses = har.script_responses(&aggregate);
+    assert_eq!(responses.len(), 2);
+    assert_eq!(
+        responses[&ScriptId { isolate: 1, id: 6 }].request.url,
+        "https://a.com/b.js"
+    );
+
+    let page = PageAggregate::from_aggregates(vec![aggregate]);
+    let (total, sphere_bytes) = page.transfer_bytes(&har);
+    assert_eq!(total, 128 + 50);
+    assert_eq!(
+        sphere_bytes.into_iter().collect::<Vec<_>>(),
+        [(Sphere::HasRequest, 128), (Sphere::UsesStorage, 50)]
+    );
+}
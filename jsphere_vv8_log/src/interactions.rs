@@ -0,0 +1,154 @@
+use super::*;
+
+/// Predicate over a new script's name and source that
+/// tells whether it drives interaction.
+pub type InteractionPredicate = Arc<dyn Fn(&ScriptName, &str) -> bool + Send + Sync>;
+
+/// Decides which scripts are interaction drivers, e.g., gremlins.js
+/// monkey testing or replay scripts, so [RecordAggregate] can mark them
+/// [ScriptInjectionType::Interaction].
+///
+/// A script is an interaction driver if any of the following holds:
+/// - It is injected and one of [InteractionDetector::markers] appears in
+///   the first [InteractionDetector::marker_window] bytes of its source.
+/// - Its [ScriptName::Url] is one of [InteractionDetector::urls].
+/// - [InteractionDetector::predicate] returns `true`.
+///
+/// The default detects the gremlins.js horde we inject.
+#[pub_fields]
+#[derive(Clone)]
+pub struct InteractionDetector {
+    markers: Vec<String>,
+    /// Number of bytes at the start of the source to search for markers.
+    marker_window: usize,
+    urls: Vec<String>,
+    predicate: Option<InteractionPredicate>,
+}
+
+impl InteractionDetector {
+    pub const GREMLINS_MARKER: &'static str = "Gremlins horde";
+
+    /// A detector that detects nothing, to add criteria to.
+    pub fn none() -> Self {
+        Self {
+            markers: Vec::new(),
+            marker_window: 100,
+            urls: Vec::new(),
+            predicate: None,
+        }
+    }
+
+    pub fn with_marker(mut self, marker: impl Into<String>) -> Self {
+        self.markers.push(marker.into());
+        self
+    }
+
+    pub fn with_marker_window(mut self, marker_window: usize) -> Self {
+        self.marker_window = marker_window;
+        self
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.urls.push(url.into());
+        self
+    }
+
+    /// Replace the predicate.
+    pub fn with_predicate(
+        mut self,
+        predicate: impl Fn(&ScriptName, &str) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.predicate = Some(Arc::new(predicate));
+        self
+    }
+
+    pub fn is_interaction(&self, name: &ScriptName, source: &str, is_injected: bool) -> bool {
+        (is_injected && self.has_marker(source))
+            || matches!(name, ScriptName::Url(url) if self.urls.contains(url))
+            || self
+                .predicate
+                .as_ref()
+                .is_some_and(|predicate| predicate(name, source))
+    }
+
+    fn has_marker(&self, source: &str) -> bool {
+        if self.markers.is_empty() {
+            return false;
+        }
+        let mut end = source.len().min(self.marker_window);
+        while !source.is_char_boundary(end) {
+            end -= 1;
+        }
+        let head = &source[..end];
+        self.markers
+            .iter()
+            .any(|marker| head.contains(marker.as_str()))
+    }
+}
+
+impl Default for InteractionDetector {
+    fn default() -> Self {
+        Self::none().with_marker(Self::GREMLINS_MARKER)
+    }
+}
+
+impl std::fmt::Debug for InteractionDetector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InteractionDetector")
+            .field("markers", &self.markers)
+            .field("marker_window", &self.marker_window)
+            .field("urls", &self.urls)
+            .field("predicate", &self.predicate.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+/// A phase of a page visit, told apart by entering and leaving
+/// interaction script contexts.
+/// Interactions are numbered from 0 in the order they start.
+#[derive_enum_everything]
+#[derive(Copy, Default)]
+pub enum Phase {
+    /// Before the crawler injects any script, i.e., while the page loads.
+    #[default]
+    Load,
+    /// After the crawler injected a script, before any interaction.
+    Idle,
+    /// Inside the `n`th interaction script context, or
+    /// the injected contexts it enters.
+    Interaction(u32),
+    /// Page scripts running after the `n`th interaction started, e.g.,
+    /// event handlers it triggered, until the next interaction.
+    PostInteraction(u32),
+}
+
+impl Phase {
+    /// Whether calls in this phase may be triggered by interaction.
+    pub fn may_interact(self) -> bool {
+        matches!(self, Self::Interaction(_) | Self::PostInteraction(_))
+    }
+
+    /// The phase after entering a script context of `injection_type`.
+    /// `n_interaction` is the number of interactions started so far.
+    pub fn enter(self, injection_type: &ScriptInjectionType, n_interaction: u32) -> Self {
+        match (self, injection_type) {
+            (Self::Interaction(_), ScriptInjectionType::Interaction) => self,
+            (_, ScriptInjectionType::Interaction) => Self::Interaction(n_interaction),
+            (Self::Load, ScriptInjectionType::Injected) => Self::Idle,
+            (Self::Interaction(n), ScriptInjectionType::Not) => Self::PostInteraction(n),
+            _ => self,
+        }
+    }
+}
+
+/// A phase and the log line where it started.
+#[pub_fields]
+#[derive_everything]
+#[derive(Copy)]
+pub struct PhaseStart {
+    phase: Phase,
+    line: u32,
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,128 @@
+use super::*;
+
+fn injection_types(aggregate: &RecordAggregate) -> Vec<(i32, ScriptInjectionType)> {
+    let mut types: Vec<_> = aggregate
+        .scripts
+        .iter()
+        .map(|(id, script)| (id.id, script.injection_type.clone()))
+        .collect();
+    types.sort_unstable();
+    types
+}
+
+fn aggregate_with(detector: InteractionDetector) -> Result<RecordAggregate> {
+    let mut aggregate = RecordAggregate {
+        interaction_detector: detector,
+        ..Default::default()
+    };
+    let lines = [
+        "~0x1",
+        r#"$5:"":/* Gremlins horde */"#,
+        r#"$6:"":replay.start()"#,
+        r#"$7:"https\://driver.test/monkey.js":monkey()"#,
+        r#"$8:"https\://a.com/a.js":/* Gremlins horde */"#,
+    ];
+    aggregate.add_lines(&lines)?;
+    Ok(aggregate)
+}
+
+#[test]
+fn default_detects_gremlins() {
+    use ScriptInjectionType::*;
+    let aggregate = aggregate_with(InteractionDetector::default()).unwrap();
+    assert_eq!(
+        injection_types(&aggregate),
+        [(5, Interaction), (6, Injected), (7, Not), (8, Not)]
+    );
+}
+
+#[test]
+fn custom_markers_urls_and_predicate() {
+    use ScriptInjectionType::*;
+    let detector = InteractionDetector::none()
+        .with_marker("replay.start")
+        .with_url("https://driver.test/monkey.js");
+    let aggregate = aggregate_with(detector.clone()).unwrap();
+    assert_eq!(
+        injection_types(&aggregate),
+        [(5, Injected), (6, Interaction), (7, Interaction), (8, Not)]
+    );
+
+    let detector = detector
+        .with_marker_window(3)
+        .with_predicate(|name, _| matches!(name, ScriptName::Url(url) if url.ends_with("a.js")));
+    let aggregate = aggregate_with(detector).unwrap();
+    assert_eq!(
+        injection_types(&aggregate),
+        [
+            (5, Injected),
+            (6, Injected),
+            (7, Interaction),
+            (8, Interaction)
+        ]
+    );
+}
+
+#[test]
+fn phases_from_context_switches() {
+    use Phase::*;
+    let mut aggregate = RecordAggregate::default();
+    let lines = [
+        "~0x1",
+        r#"$5:"https\://a.com/a.js":fetch()"#,
+        "!5",
+        r#"c1:%fetch:{1,Window}:"/""#,
+        r#"$6:"":window.gremlins = {}"#,
+        "!6",
+        "!5",
+        r#"c1:%fetch:{1,Window}:"/""#,
+        r#"$7:"":/* Gremlins horde */"#,
+        "!7",
+        r#"c2:%dispatchEvent:{2,HTMLDivElement}:{3,MouseEvent}"#,
+        "!5",
+        r#"c1:%fetch:{1,Window}:"/""#,
+        "!7",
+        "!6",
+        "!5",
+        r#"c1:%fetch:{1,Window}:"/""#,
+    ];
+    aggregate.add_lines(&lines).unwrap();
+    assert_eq!(
+        aggregate.phase_starts,
+        [
+            PhaseStart {
+                phase: Idle,
+                line: 5
+            },
+            PhaseStart {
+                phase: Interaction(0),
+                line: 9
+            },
+            PhaseStart {
+                phase: PostInteraction(0),
+                line: 11
+            },
+            PhaseStart {
+                phase: Interaction(1),
+                line: 13
+            },
+            PhaseStart {
+                phase: PostInteraction(1),
+                line: 15
+            },
+        ]
+    );
+
+    let fetch = &aggregate.scripts[&ScriptId { isolate: 1, id: 5 }].api_calls[&ApiCall {
+        api_type: ApiType::Function,
+        this: "Window".into(),
+        attr: Some("fetch".into()),
+        ..Default::default()
+    }];
+    assert_eq!(
+        fetch.phases,
+        [Load, Idle, PostInteraction(0), PostInteraction(1)]
+    );
+    assert_eq!(fetch.i_may_interact, Some(2));
+    assert_eq!(fetch.counts_by_phase()[&PostInteraction(0)], 1);
+}
@@ -2,7 +2,7 @@ use super::*;
 
 /// JavaScript and V8 values. As per VV8 output, strings are
 /// ASCII-encoded, escaping unprintable characters as `\xNN` and
-/// Unicode characters as `\uNNNN`.
+/// Unicode characters as `\uNNNN`; they are decoded back with [unescape].
 #[derive_float_enum_everything]
 pub enum JSValue {
     String(String),
@@ -38,36 +38,145 @@ pub enum JSValue {
 
 impl From<&str> for JSValue {
     fn from(value: &str) -> Self {
+        JSValueRef::from(value).into_owned()
+    }
+}
+
+/// Borrowed form of [JSValue] that only allocates for strings that
+/// need unescaping, or for [JSValueRef::ObjectLiteral] pairs.
+#[derive_float_enum_everything]
+pub enum JSValueRef<'a> {
+    String(Cow<'a, str>),
+    Int(i64),
+    Float(f64),
+    RegEx(Cow<'a, str>),
+    Boolean(bool),
+    Null,
+    Undefined,
+    /// "V8-specific oddball type that leaks into the log data".
+    V8Specific,
+    Function {
+        name: Cow<'a, str>,
+        is_user_fn: bool,
+    },
+    /// Anonymous function.
+    Lambda,
+    /// Object with the name of the constructor function.
+    Object {
+        index: i32,
+        constructor: Cow<'a, str>,
+    },
+    /// Object with only the index.
+    ObjectUnknown(i32),
+    /// Literal object.
+    ObjectLiteral {
+        index: i32,
+        pairs: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+    },
+    /// A value the logging code is unsure about.
+    Unsure,
+}
+
+impl JSValueRef<'_> {
+    /// Convert to [JSValue] by allocating the borrowed strings.
+    pub fn into_owned(self) -> JSValue {
+        match self {
+            Self::String(s) => JSValue::String(s.into_owned()),
+            Self::Int(n) => JSValue::Int(n),
+            Self::Float(n) => JSValue::Float(n),
+            Self::RegEx(r) => JSValue::RegEx(r.into_owned()),
+            Self::Boolean(b) => JSValue::Boolean(b),
+            Self::Null => JSValue::Null,
+            Self::Undefined => JSValue::Undefined,
+            Self::V8Specific => JSValue::V8Specific,
+            Self::Function { name, is_user_fn } => JSValue::Function {
+                name: name.into_owned(),
+                is_user_fn,
+            },
+            Self::Lambda => JSValue::Lambda,
+            Self::Object { index, constructor } => JSValue::Object {
+                index,
+                constructor: constructor.into_owned(),
+            },
+            Self::ObjectUnknown(index) => JSValue::ObjectUnknown(index),
+            Self::ObjectLiteral { index, pairs } => JSValue::ObjectLiteral {
+                index,
+                pairs: pairs
+                    .into_iter()
+                    .map(|(key, val)| (key.into_owned(), val.into_owned()))
+                    .collect(),
+            },
+            Self::Unsure => JSValue::Unsure,
+        }
+    }
+}
+
+impl From<JSValue> for JSValueRef<'static> {
+    /// Wrap the owned strings without copying them.
+    fn from(value: JSValue) -> Self {
+        match value {
+            JSValue::String(s) => Self::String(s.into()),
+            JSValue::Int(n) => Self::Int(n),
+            JSValue::Float(n) => Self::Float(n),
+            JSValue::RegEx(r) => Self::RegEx(r.into()),
+            JSValue::Boolean(b) => Self::Boolean(b),
+            JSValue::Null => Self::Null,
+            JSValue::Undefined => Self::Undefined,
+            JSValue::V8Specific => Self::V8Specific,
+            JSValue::Function { name, is_user_fn } => Self::Function {
+                name: name.into(),
+                is_user_fn,
+            },
+            JSValue::Lambda => Self::Lambda,
+            JSValue::Object { index, constructor } => Self::Object {
+                index,
+                constructor: constructor.into(),
+            },
+            JSValue::ObjectUnknown(index) => Self::ObjectUnknown(index),
+            JSValue::ObjectLiteral { index, pairs } => Self::ObjectLiteral {
+                index,
+                pairs: pairs
+                    .into_iter()
+                    .map(|(key, val)| (key.into(), val.into()))
+                    .collect(),
+            },
+            JSValue::Unsure => Self::Unsure,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for JSValueRef<'a> {
+    fn from(value: &'a str) -> Self {
         match value {
-            "#F" => JSValue::Boolean(false),
-            "#T" => JSValue::Boolean(true),
-            "#N" => JSValue::Null,
-            "#U" => JSValue::Undefined,
-            "#?" => JSValue::V8Specific,
-            "<anonymous>" => JSValue::Lambda,
-            "?" => JSValue::Unsure,
+            "#F" => JSValueRef::Boolean(false),
+            "#T" => JSValueRef::Boolean(true),
+            "#N" => JSValueRef::Null,
+            "#U" => JSValueRef::Undefined,
+            "#?" => JSValueRef::V8Specific,
+            "<anonymous>" => JSValueRef::Lambda,
+            "?" => JSValueRef::Unsure,
             _ => {
                 if value.starts_with("\"") && value.ends_with("\"") && value.len() >= 2 {
                     // "<string>"
-                    JSValue::String(unescape_colon(&value[1..value.len() - 1]))
+                    JSValueRef::String(unescape(&value[1..value.len() - 1]))
                 } else if value.starts_with('/') && value.ends_with('/') && value.len() >= 2 {
                     // "/regex/"
-                    JSValue::RegEx(unescape_colon(&value[1..value.len() - 1]))
+                    JSValueRef::RegEx(unescape(&value[1..value.len() - 1]))
                 } else if value.starts_with('{') && value.ends_with('}') {
                     // "{Object}"
-                    parse_js_object(value).unwrap_or(JSValue::ObjectUnknown(-1))
+                    parse_js_object(value).unwrap_or(JSValueRef::ObjectUnknown(-1))
                 } else if let Ok(n) = value.parse() {
-                    JSValue::Int(n)
+                    JSValueRef::Int(n)
                 } else if let Ok(n) = value.parse() {
-                    JSValue::Float(n)
+                    JSValueRef::Float(n)
                 } else if let Some(stripped) = value.strip_prefix("%") {
-                    JSValue::Function {
-                        name: unescape_colon(stripped),
+                    JSValueRef::Function {
+                        name: unescape(stripped),
                         is_user_fn: false,
                     }
                 } else {
-                    JSValue::Function {
-                        name: unescape_colon(value),
+                    JSValueRef::Function {
+                        name: unescape(value),
                         is_user_fn: true,
                     }
                 }
@@ -76,7 +185,7 @@ impl From<&str> for JSValue {
     }
 }
 
-fn parse_js_object(value: &str) -> Option<JSValue> {
+fn parse_js_object(value: &str) -> Option<JSValueRef<'_>> {
     let mut splits = value[1..value.len() - 1].split(',');
     let index = splits.next()?.parse().ok()?;
     Some(if let Some(constructor) = splits.next() {
@@ -86,19 +195,19 @@ fn parse_js_object(value: &str) -> Option<JSValue> {
             let mut pairs = Vec::with_capacity(4); // Usually big enough.
             for pair in [pair0, pair1].into_iter().chain(splits) {
                 let (key, val) = pair.split_once(r"\:")?;
-                pairs.push((unescape_colon(key), unescape_colon(val)));
+                pairs.push((unescape(key), unescape(val)));
             }
             pairs.shrink_to_fit();
-            JSValue::ObjectLiteral { index, pairs }
+            JSValueRef::ObjectLiteral { index, pairs }
         } else {
             // {index,constructor}
-            JSValue::Object {
+            JSValueRef::Object {
                 index,
-                constructor: unescape_colon(constructor),
+                constructor: unescape(constructor),
             }
         }
     } else {
         // {index}
-        JSValue::ObjectUnknown(index)
+        JSValueRef::ObjectUnknown(index)
     })
 }
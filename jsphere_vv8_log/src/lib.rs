@@ -5,31 +5,123 @@
 #![allow(clippy::len_without_is_empty)]
 
 use std::{
-    collections::HashMap,
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     fs::{self, DirEntry, File},
-    io::{self, BufRead, BufReader},
-    path::Path,
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock},
 };
 
 pub use aggregating::{
-    ApiCall, ApiType, RecordAggregate, ScriptAggregate, ScriptInjectionType, ScriptName,
+    ApiCall, ApiType, CallLines, ContentHash, RecordAggregate, RewrittenBlock, ScriptAggregate,
+    ScriptId, ScriptInjectionType, ScriptName, SourcePosition, ValueKind,
 };
-pub use js_values::JSValue;
-use lazy_regex::regex_is_match;
-pub use log_files::{read_logs, LogFile, LogFileInfo};
-pub use log_records::{LogRecord, LogRecordErr, ID_UNSURE};
+pub use classify::{classify, explain, Classification, Evidence, Sphere, SphereRules, SphereSet};
+pub use corpus_index::{CorpusIndex, IndexedScript};
+pub use crawl_corpus::{decode_uri_component, CrawlCorpus, HarFile, ReachableFile, Site, Trial};
+pub use fingerprint::{fingerprint, Library, LibraryKind, LibraryRules};
+use flate2::read::MultiGzDecoder;
+pub use har_files::{
+    Har, HarContent, HarEntry, HarHeader, HarInitiator, HarRequest, HarResponse, HarTimings,
+};
+pub use interactions::{InteractionDetector, InteractionPredicate, Phase, PhaseStart};
+pub use js_values::{JSValue, JSValueRef};
+use lazy_regex::{regex, regex_captures, regex_is_match, Regex};
+pub use log_archives::{for_each_archived_log, ArchivedLogFile};
+pub use log_files::{
+    read_logs, stream_logs, Decompressed, LogCompression, LogFile, LogFileInfo, LogStream, ReadErr,
+};
+pub use log_records::{LogRecord, LogRecordErr, LogRecordRef, ID_UNSURE};
+pub use log_stitching::{StitchedLog, StitchedStream};
+pub use page_aggregates::{PageAggregate, PageScript, CRAWLER_BACK_NAVIGATION_SOURCE};
 use rayon::prelude::*;
+pub use reachable_files::{is_same_site, Reachable};
 pub use record_lines::SplitRecordLine;
+pub use script_trees::{RolledUpScript, ScriptTree};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
 use shame::prelude::*;
+pub use trial_consistency::{ScriptConsistency, TrialConsistency, TrialScriptKey};
 
 pub mod aggregating;
+pub mod classify;
+pub mod corpus_index;
+pub mod crawl_corpus;
+pub mod fingerprint;
+pub mod har_files;
+pub mod interactions;
 pub mod js_values;
+pub mod log_archives;
 pub mod log_files;
 pub mod log_records;
+pub mod log_stitching;
+pub mod page_aggregates;
+pub mod reachable_files;
 pub mod record_lines;
+pub mod script_trees;
+pub mod trial_consistency;
+
+/// Decode the escapes VV8 uses to keep its log ASCII-only:
+/// `\xNN` (Latin-1), `\uNNNN` (UTF-16 code unit, combining surrogate pairs),
+/// `\\`, and `\:`.
+/// Malformed escapes, e.g., lone surrogates or non-hex digits,
+/// are kept verbatim so no information is lost.
+pub fn unescape(data: &str) -> Cow<'_, str> {
+    if !data.contains('\\') {
+        return Cow::Borrowed(data);
+    }
+    let mut output = String::with_capacity(data.len());
+    let mut rest = data;
+    while let Some(index) = rest.find('\\') {
+        output.push_str(&rest[..index]);
+        rest = &rest[index..];
+        let (decoded, n_consumed) = match rest.as_bytes().get(1) {
+            Some(b'\\') => (Some('\\'), 2),
+            Some(b':') => (Some(':'), 2),
+            Some(b'x') => (parse_hex(&rest[2..], 2).and_then(char::from_u32), 4),
+            Some(b'u') => match parse_hex(&rest[2..], 4) {
+                Some(high @ 0xD800..=0xDBFF) => {
+                    let low = rest
+                        .get(6..)
+                        .and_then(|after| after.strip_prefix(r"\u"))
+                        .and_then(|after| parse_hex(after, 4));
+                    match low {
+                        Some(low @ 0xDC00..=0xDFFF) => {
+                            let code_point = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                            (char::from_u32(code_point), 12)
+                        }
+                        _ => (None, 0),
+                    }
+                }
+                Some(code_unit) => (char::from_u32(code_unit), 6),
+                None => (None, 0),
+            },
+            _ => (None, 0),
+        };
+        match decoded {
+            Some(char) => {
+                output.push(char);
+                rest = &rest[n_consumed..];
+            }
+            None => {
+                // Keep the backslash and continue after it.
+                output.push('\\');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    Cow::Owned(output)
+}
 
-fn unescape_colon(data: &str) -> String {
-    data.replace(r"\:", ":").replace(r"\\", r"\")
+/// Parse exactly `n_digit` hex digits at the start of `data`.
+fn parse_hex(data: &str, n_digit: usize) -> Option<u32> {
+    let digits = data.get(..n_digit)?;
+    match digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        true => u32::from_str_radix(digits, 16).ok(),
+        false => None,
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,85 @@
+use super::*;
+
+/// A [LogFile] read from a tarball of crawl results, where it was at
+/// `<site>/<trial>/vv8-*.log`, e.g.,
+/// `headless_browser/target/youtube.com/0/vv8-1726285073665-87-87-chrome.0.log`.
+#[derive_float_everything]
+#[pub_fields]
+pub struct ArchivedLogFile {
+    /// The site, decoded from its directory name, e.g., `youtube.com`.
+    /// Empty if the log file is not nested deep enough.
+    site: String,
+    /// Path of the trial directory inside the archive, e.g.,
+    /// `headless_browser/target/youtube.com/0`.
+    trial_path: PathBuf,
+    log: LogFile,
+}
+
+/// Read and parse each log file in the `.tar`, `.tar.gz`, or `.tar.zst`
+/// archive at `path` and pass it to `callback`, in archive order.
+/// Log files are matched by their names (see [LogFileInfo]) and
+/// may themselves be compressed.
+pub fn for_each_archived_log<P: AsRef<Path>>(
+    path: P,
+    mut callback: impl FnMut(ArchivedLogFile),
+) -> Result<()> {
+    let path = path.as_ref();
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .context("Archive file name")?;
+    let file = File::open(path).context("Opening archive")?;
+    let reader = LogCompression::from_file_name(file_name)
+        .decompress(file)
+        .context("Decompressing archive")?;
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries().context("Reading archive entries")? {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                error!(?err, "Reading archive entry");
+                continue;
+            }
+        };
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let entry_path = match entry.path() {
+            Ok(entry_path) => entry_path.into_owned(),
+            Err(err) => {
+                error!(?err, "Reading archive entry path");
+                continue;
+            }
+        };
+        match read_archived_log(&entry_path, entry) {
+            Ok(log) => callback(log),
+            Err(err) => debug!(?entry_path, ?err, "Did not parse as log file"),
+        }
+    }
+    Ok(())
+}
+
+fn read_archived_log<R: Read>(entry_path: &Path, entry: R) -> Result<ArchivedLogFile> {
+    let file_name = entry_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .context("Entry file name")?;
+    let info: LogFileInfo = file_name.try_into()?;
+    let reader = LogCompression::from_file_name(file_name).decoder(entry)?;
+    let log = LogFile::from_reader(info, reader);
+    let trial_path = entry_path.parent().unwrap_or(Path::new("")).to_owned();
+    let site = trial_path
+        .parent()
+        .and_then(|site_path| site_path.file_name())
+        .map(|site| site.to_string_lossy())
+        .map(|site| decode_uri_component(&site).unwrap_or_else(|| site.into_owned()))
+        .unwrap_or_default();
+    Ok(ArchivedLogFile {
+        site,
+        trial_path,
+        log,
+    })
+}
+
+#[cfg(test)]
+mod tests;
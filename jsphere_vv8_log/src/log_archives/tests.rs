@@ -0,0 +1,50 @@
+use std::io::Write;
+
+use super::*;
+
+fn append_file(builder: &mut tar::Builder<impl Write>, path: &str, content: &[u8]) {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, path, content).unwrap();
+}
+
+#[test]
+fn read_tar_gz_archive() {
+    let dir = tempfile::tempdir().unwrap();
+    let archive_path = dir.path().join("crawl.tar.gz");
+    let encoder = flate2::write::GzEncoder::new(
+        File::create(&archive_path).unwrap(),
+        flate2::Compression::default(),
+    );
+    let mut builder = tar::Builder::new(encoder);
+    let content = b"~0x2a3800370000\n!?\n";
+    append_file(
+        &mut builder,
+        "target/youtube.com/0/vv8-1726285073665-87-87-chrome.0.log",
+        content,
+    );
+    let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    gz.write_all(content).unwrap();
+    append_file(
+        &mut builder,
+        "target/google.com/3/vv8-1726285073666-88-88-chrome.0.log.gz",
+        &gz.finish().unwrap(),
+    );
+    append_file(&mut builder, "target/google.com/3.har", b"{}");
+    builder.into_inner().unwrap().finish().unwrap();
+
+    let mut logs = Vec::new();
+    for_each_archived_log(&archive_path, |log| logs.push(log)).unwrap();
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0].site, "youtube.com");
+    assert_eq!(logs[0].trial_path, Path::new("target/youtube.com/0"));
+    assert_eq!(logs[0].log.info.pid, 87);
+    assert_eq!(logs[1].site, "google.com");
+    assert_eq!(logs[1].trial_path, Path::new("target/google.com/3"));
+    for log in &logs {
+        assert_eq!(log.log.records.len(), 2);
+        assert!(log.log.read_errs.is_empty());
+    }
+}
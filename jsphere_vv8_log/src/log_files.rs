@@ -42,6 +42,39 @@ impl TryFrom<&Path> for LogFile {
 
     #[inline]
     fn try_from(path: &Path) -> Result<Self, Self::Error> {
+        LogFile::stream(path).map(LogFile::from_stream)
+    }
+}
+
+impl LogFile {
+    /// Parse all records from `reader`, the uncompressed content of
+    /// the log file that `info` was parsed from.
+    pub fn from_reader<R: Read>(info: LogFileInfo, reader: R) -> Self {
+        Self::from_stream(LogStream::new(info, BufReader::new(reader)))
+    }
+
+    /// Number of lines in the log file, as far as parsing saw them.
+    pub fn n_lines(&self) -> usize {
+        let last_record = self.records.last().map(|(line_n, _)| *line_n);
+        let last_read_err = self.read_errs.last().map(|read_err| read_err.line_n);
+        last_record
+            .max(last_read_err)
+            .map_or(0, |line_n| line_n + 1)
+    }
+
+    fn from_stream<R: BufRead>(stream: LogStream<R>) -> Self {
+        let info = stream.info.clone();
+        let (records, read_errs) = parse_log_file(stream);
+        LogFile {
+            info,
+            records,
+            read_errs,
+        }
+    }
+
+    /// Open the log file at `path` for parsing its records one line at
+    /// a time, without holding all of them in memory.
+    pub fn stream(path: &Path) -> Result<LogStream, LogFileErr> {
         if !path.is_file() {
             return Err(LogFileErr::NotAFile);
         }
@@ -52,12 +85,127 @@ impl TryFrom<&Path> for LogFile {
             .map_err(|_| LogFileErr::NotALogFileName)?;
 
         let file = File::open(path).map_err(LogFileErr::OpenFileError)?;
-        let (records, read_errs) = parse_log_file(file);
-        Ok(LogFile {
+        let reader = LogCompression::from_file_name(file_name_str)
+            .decompress(file)
+            .map_err(LogFileErr::OpenFileError)?;
+        Ok(LogStream::new(info, BufReader::new(reader)))
+    }
+}
+
+/// Lazily stream the log files in the specified directory,
+/// opening each one only when it is reached.
+pub fn stream_logs<P: AsRef<Path>>(dir: P) -> Result<impl Iterator<Item = LogStream>> {
+    let entries = fs::read_dir(dir).context("Reading directory")?;
+    Ok(entries.filter_map(|entry| {
+        let path = entry
+            .inspect_err(|err| error!(?err, "Reading directory entry"))
+            .ok()?
+            .path();
+        LogFile::stream(&path)
+            .inspect_err(|err| debug!(?path, ?err, "Did not open as log file"))
+            .ok()
+    }))
+}
+
+/// Iterator over the records of a log file, parsed as they are read.
+/// Yields the line number from 0 and the record, or the read error.
+pub struct LogStream<R = BufReader<Box<dyn Read + Send>>> {
+    /// The information in the file name.
+    info: LogFileInfo,
+    reader: R,
+    line_n: usize,
+    /// Buffer reused across lines.
+    buf: String,
+    done: bool,
+}
+
+impl<R: BufRead> LogStream<R> {
+    /// Stream the records read from `reader`, e.g., the decompressed content
+    /// of the log file that `info` was parsed from.
+    pub fn new(info: LogFileInfo, reader: R) -> Self {
+        Self {
             info,
-            records,
-            read_errs,
-        })
+            reader,
+            line_n: 0,
+            buf: String::with_capacity(1024),
+            done: false,
+        }
+    }
+
+    /// The information in the file name.
+    pub fn info(&self) -> &LogFileInfo {
+        &self.info
+    }
+
+    /// Like [Iterator::next], but the record borrows the line read, so
+    /// [RecordAggregate::add] only allocates for the strings it keeps.
+    /// Loop with `while let Some(result) = stream.next_ref()`.
+    pub fn next_ref(&mut self) -> Option<Result<(usize, LogRecordRef<'_>), ReadErr>> {
+        match self.read_line()? {
+            Ok(line_n) => Some(self.parse_line(line_n)),
+            Err(err) => Some(Err(err)),
+        }
+    }
+
+    /// Read the next line into the buffer and return its line number.
+    pub(crate) fn read_line(&mut self) -> Option<Result<usize, ReadErr>> {
+        if self.done {
+            return None;
+        }
+        self.buf.clear();
+        let line_n = self.line_n;
+        match self.reader.read_line(&mut self.buf) {
+            Ok(0) => {
+                self.done = true;
+                return None;
+            }
+            Ok(_) => {}
+            Err(err) => {
+                warn!(?err, "LogFile: reading line");
+                // Only invalid UTF-8 lets us carry on with the next line.
+                self.done = err.kind() != io::ErrorKind::InvalidData;
+                self.line_n += 1;
+                let line = err.to_string();
+                let err = LogRecordErr::UnknownLogRecordType;
+                return Some(Err(ReadErr { line_n, line, err }));
+            }
+        }
+        self.line_n += 1;
+        Some(Ok(line_n))
+    }
+
+    /// Parse the line last read by [LogStream::read_line] as `line_n`.
+    pub(crate) fn parse_line(&self, line_n: usize) -> Result<(usize, LogRecordRef<'_>), ReadErr> {
+        let line = self.buf.strip_suffix('\n').unwrap_or(&self.buf);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match LogRecordRef::try_from(line) {
+            Ok(record) => Ok((line_n, record)),
+            Err(err) => {
+                warn!(line, ?err, "LogFile: parsing line");
+                let line = line.to_owned();
+                Err(ReadErr { line_n, line, err })
+            }
+        }
+    }
+
+    /// Number the following lines from `line_n` on, e.g., to continue
+    /// the line numbers of a previous part (see [StitchedStream]).
+    pub(crate) fn continue_from(&mut self, line_n: usize) {
+        self.line_n = line_n;
+    }
+
+    /// The line number of the next line to read.
+    pub(crate) fn line_n(&self) -> usize {
+        self.line_n
+    }
+}
+
+impl<R: BufRead> Iterator for LogStream<R> {
+    type Item = Result<(usize, LogRecord), ReadErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_ref()
+            .map(|result| result.map(|(line_n, record)| (line_n, record.into_owned())))
     }
 }
 
@@ -86,14 +234,13 @@ pub enum LogFileErr {
 
 /// Parse the log file content into records.
 /// Returns log records sorted by line numbers, alone with read errors.
-fn parse_log_file(file: File) -> (Vec<(usize, LogRecord)>, Vec<ReadErr>) {
-    let file_reader = BufReader::new(file);
+fn parse_log_file<R: BufRead>(stream: LogStream<R>) -> (Vec<(usize, LogRecord)>, Vec<ReadErr>) {
     let mut records = Vec::with_capacity(1024);
     let mut read_errs = Vec::with_capacity(32);
-    for (line_n, line) in file_reader.lines().enumerate() {
-        match parse_log_file_line(line) {
-            Ok(record) => records.push((line_n, record)),
-            Err((line, err)) => read_errs.push(ReadErr { line_n, line, err }),
+    for result in stream {
+        match result {
+            Ok(record) => records.push(record),
+            Err(read_err) => read_errs.push(read_err),
         }
     }
 
@@ -102,20 +249,10 @@ fn parse_log_file(file: File) -> (Vec<(usize, LogRecord)>, Vec<ReadErr>) {
     (records, read_errs)
 }
 
-fn parse_log_file_line(line: io::Result<String>) -> Result<LogRecord, (String, LogRecordErr)> {
-    let line = line.map_err(|err| {
-        warn!(?err, "LogFile: reading line");
-        (err.to_string(), LogRecordErr::UnknownLogRecordType)
-    })?;
-    line.as_str().try_into().map_err(|err| {
-        warn!(line, ?err, "LogFile: parsing line");
-        (line, err)
-    })
-}
-
 /// Information in the log file name VV8 creates:
 /// `vv8-$TIMESTAMP-$PID-$TID-$THREAD_NAME.log`. E.g.,
 /// `vv8-1726285073665-87-87-chrome.0.log`.
+/// Archived logs may carry an extra `.gz` or `.zst` suffix (see [LogCompression]).
 #[derive_everything]
 #[pub_fields]
 pub struct LogFileInfo {
@@ -129,6 +266,15 @@ pub struct LogFileInfo {
     thread_name: String,
 }
 
+impl LogFileInfo {
+    /// The numeric suffix of the thread name, e.g., 1 for `chrome.1`.
+    /// VV8 increments it when it continues a thread's log in a new file.
+    pub fn thread_index(&self) -> Option<u32> {
+        let (_, suffix) = self.thread_name.rsplit_once('.')?;
+        suffix.parse().ok()
+    }
+}
+
 impl TryFrom<&str> for LogFileInfo {
     type Error = LogFileInfoErr;
 
@@ -143,7 +289,8 @@ impl TryFrom<&str> for LogFileInfo {
 
 /// Assuming the file name is a VV8 log file name.
 fn do_parse_log_file_info(file_name: &str) -> Result<LogFileInfo, LogFileInfoErr> {
-    let middle = &file_name[4..(file_name.len() - 4)];
+    let extension_len = LogCompression::from_file_name(file_name).extension().len();
+    let middle = &file_name[4..(file_name.len() - 4 - extension_len)];
     let parts: Vec<&str> = middle.split('-').collect();
     if parts.len() != 4 {
         return Err(LogFileInfoErr::NotALogFileName);
@@ -164,7 +311,81 @@ fn do_parse_log_file_info(file_name: &str) -> Result<LogFileInfo, LogFileInfoErr
 
 #[inline]
 pub fn is_not_vv8_log_file(file_name: &str) -> bool {
-    !file_name.ends_with(".log") || !file_name.starts_with("vv8-")
+    let extension = LogCompression::from_file_name(file_name).extension();
+    let Some(log_file_name) = file_name.strip_suffix(extension) else {
+        return true;
+    };
+    !log_file_name.ends_with(".log") || !log_file_name.starts_with("vv8-")
+}
+
+/// How a log file is compressed, judging by its file name, e.g.,
+/// `vv8-1726285073665-87-87-chrome.0.log.gz`.
+#[derive_everything]
+#[derive(Copy)]
+pub enum LogCompression {
+    /// Plain `.log` file.
+    #[default]
+    None,
+    /// `.gz` suffix.
+    Gzip,
+    /// `.zst` suffix.
+    Zstd,
+}
+
+impl LogCompression {
+    pub fn from_file_name(file_name: &str) -> Self {
+        if file_name.ends_with(".gz") {
+            Self::Gzip
+        } else if file_name.ends_with(".zst") {
+            Self::Zstd
+        } else {
+            Self::None
+        }
+    }
+
+    /// The file name extension after `.log`, including the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::Gzip => ".gz",
+            Self::Zstd => ".zst",
+        }
+    }
+
+    /// Wrap `reader` to decompress its content on the fly.
+    pub fn decompress<R: Read + Send + 'static>(
+        self,
+        reader: R,
+    ) -> io::Result<Box<dyn Read + Send>> {
+        Ok(Box::new(self.decoder(reader)?))
+    }
+
+    /// Like [Self::decompress], but without boxing, so `reader` may borrow,
+    /// e.g., an entry of a tar archive.
+    pub fn decoder<R: Read>(self, reader: R) -> io::Result<Decompressed<R>> {
+        Ok(match self {
+            Self::None => Decompressed::None(reader),
+            Self::Gzip => Decompressed::Gzip(MultiGzDecoder::new(reader)),
+            Self::Zstd => Decompressed::Zstd(zstd::Decoder::new(reader)?),
+        })
+    }
+}
+
+/// Reader over the decompressed content of `R`, see [LogCompression::decoder].
+pub enum Decompressed<R: Read> {
+    None(R),
+    Gzip(MultiGzDecoder<R>),
+    Zstd(zstd::Decoder<'static, BufReader<R>>),
+}
+
+impl<R: Read> Read for Decompressed<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::None(reader) => reader.read(buf),
+            Self::Gzip(reader) => reader.read(buf),
+            Self::Zstd(reader) => reader.read(buf),
+        }
+    }
 }
 
 /// Error when parsing a log file name to [LogFileInfo].
@@ -10,6 +10,14 @@ fn vv8_log_file_name() {
     };
     let actual = "vv8-1726285073665-87-87-chrome.0.log".try_into().unwrap();
     assert_eq!(expected, actual);
+    let actual = "vv8-1726285073665-87-87-chrome.0.log.gz"
+        .try_into()
+        .unwrap();
+    assert_eq!(expected, actual);
+    let actual = "vv8-1726285073665-87-87-chrome.0.log.zst"
+        .try_into()
+        .unwrap();
+    assert_eq!(expected, actual);
 }
 
 #[test]
@@ -17,4 +25,100 @@ fn not_vv8_log_file_name() {
     assert!(is_not_vv8_log_file("test.log"));
     assert!(is_not_vv8_log_file("vv8-1726285073665-87-87-chrome.0"));
     assert!(is_not_vv8_log_file("papers.tar.gz"));
+    assert!(is_not_vv8_log_file("vv8-1726285073665-87-87-chrome.0.gz"));
+    assert!(is_not_vv8_log_file(
+        "vv8-1726285073665-87-87-chrome.0.log.xz"
+    ));
+}
+
+#[test]
+fn stream_log_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("vv8-1726285073665-87-87-chrome.0.log");
+    fs::write(&path, "~0x2a3800370000\r\n!?\nbad line\n@?").unwrap();
+
+    let stream = LogFile::stream(&path).unwrap();
+    assert_eq!(stream.info().thread_name, "chrome.0");
+    let results: Vec<_> = stream.collect();
+    assert_eq!(results.len(), 4);
+    assert_eq!(
+        results[0],
+        Ok((
+            0,
+            LogRecord::IsolateContext {
+                address: 0x2a3800370000
+            }
+        ))
+    );
+    assert_eq!(
+        results[2],
+        Err(ReadErr {
+            line_n: 2,
+            line: "bad line".into(),
+            err: LogRecordErr::UnknownLogRecordType,
+        })
+    );
+    assert_eq!(
+        results[3],
+        Ok((
+            3,
+            LogRecord::WindowOrigin {
+                value: JSValue::Unsure
+            }
+        ))
+    );
+
+    let log_file = LogFile::try_from(path.as_path()).unwrap();
+    assert_eq!(log_file.records.len(), 3);
+    assert_eq!(log_file.read_errs.len(), 1);
+}
+
+#[test]
+fn aggregate_borrowed_records() {
+    let info = "vv8-1726285073665-87-87-chrome.0.log".try_into().unwrap();
+    let content = "~0x1\n$5:\"https\\://a.com/a.js\":fetch(\"/\")\n!5\nbad line\nc1:%fetch:{1,Window}:\"/\"\n";
+    let mut stream = LogStream::new(info, content.as_bytes());
+    let mut aggregate = RecordAggregate::default();
+    let mut n_read_err = 0;
+    while let Some(result) = stream.next_ref() {
+        match result {
+            Ok((line, record)) => aggregate.add(line as u32, record).unwrap(),
+            Err(_) => n_read_err += 1,
+        }
+    }
+    assert_eq!(n_read_err, 1);
+    let script = &aggregate.scripts[&ScriptId { isolate: 1, id: 5 }];
+    assert_eq!(script.source, r#"fetch("/")"#);
+    let fetch = &script.api_calls[&ApiCall {
+        api_type: ApiType::Function,
+        this: "Window".into(),
+        attr: Some("fetch".into()),
+        ..Default::default()
+    }];
+    assert_eq!(fetch.lines, [4]);
+}
+
+#[test]
+fn read_compressed_logs() {
+    use std::io::Write;
+    let dir = tempfile::tempdir().unwrap();
+    let content = b"~0x2a3800370000\n!?\n";
+    let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    gz.write_all(content).unwrap();
+    fs::write(
+        dir.path().join("vv8-1-2-3-chrome.0.log.gz"),
+        gz.finish().unwrap(),
+    )
+    .unwrap();
+    let zst = zstd::encode_all(&content[..], 0).unwrap();
+    fs::write(dir.path().join("vv8-4-5-6-chrome.0.log.zst"), zst).unwrap();
+    fs::write(dir.path().join("vv8-7-8-9-chrome.0.log"), content).unwrap();
+
+    let mut logs = read_logs(dir.path()).unwrap();
+    logs.sort_unstable_by_key(|log| log.info.timestamp);
+    assert_eq!(logs.len(), 3);
+    for log in logs {
+        assert_eq!(log.records.len(), 2, "{:?}", log.info);
+        assert!(log.read_errs.is_empty(), "{:?}", log.info);
+    }
 }
@@ -26,8 +26,7 @@ pub enum LogRecord {
         /// E.g., `"chrome\://headless/headless_command.js"` or `""`.
         name: JSValue,
         /// The full script source, with unprintable and
-        /// Unicode characters escaped (see [JSValue]).
-        // TODO: Unescape the strings.
+        /// Unicode characters decoded by [unescape].
         source: String,
     },
 
@@ -85,6 +84,91 @@ pub enum LogRecord {
     },
 }
 
+/// Borrowed form of [LogRecord] whose strings point into the log line
+/// unless they need unescaping.
+#[derive_float_enum_everything]
+pub enum LogRecordRef<'a> {
+    /// `~`: (Possibly) a new isolate context, a namespace for e.g. script IDs.
+    IsolateContext {
+        /// Isolate address that is unique per-process, e.g., `0x2a3800370000`.
+        address: i64,
+    },
+
+    /// `@`: (Possibly) a new `window.origin` value of
+    /// the current isolate context.
+    WindowOrigin {
+        /// Contains either the [JSValue::String] retrieved from the property,
+        /// or [JSValue::Unsure] if that is unavailable.
+        value: JSValueRef<'a>,
+    },
+
+    /// `$`: Script provenance.
+    ScriptProvenance {
+        /// The new script's ID, e.g., 5.
+        id: i32,
+        /// Either the new script's name in URL form as [JSValue::String], or
+        /// the parent script's ID as [JSValue::Int] in case of `eval`.
+        /// E.g., `"chrome\://headless/headless_command.js"` or `""`.
+        name: JSValueRef<'a>,
+        /// The full script source, with unprintable and
+        /// Unicode characters decoded by [unescape].
+        source: Cow<'a, str>,
+    },
+
+    /// `!`: Execution context for subsequent log records.
+    ExecutionContext {
+        /// Active script ID in the current isolate script-ID-space, e.g., 5.
+        /// If set to [ID_UNSURE], means unsure.
+        script_id: i32,
+    },
+
+    /// `c`: Function call.
+    FunctionCall {
+        /// Character offset within the script, e.g., 27 or -1.
+        offset: i32,
+        /// Function object/name, e.g., `%atob`.
+        method: Cow<'a, str>,
+        is_user_fn: bool,
+        /// Receiver (`this` value), e.g., `{729551,Window}`.
+        receiver: JSValueRef<'a>,
+        /// Positional arguments to the function.
+        arguments: Vec<JSValueRef<'a>>,
+    },
+
+    /// `n`: "Construction" function call, e.g., `new Foo(1, 2, 3)`.
+    ConstructionCall {
+        /// Character offset within the script, e.g., 23.
+        offset: i32,
+        /// Function object/name, e.g., `Image`.
+        method: Cow<'a, str>,
+        is_user_fn: bool,
+        /// Positional arguments to the function.
+        arguments: Vec<JSValueRef<'a>>,
+    },
+
+    /// `g`: Getting property value, e.g., `foo.bar`.
+    GetProperty {
+        /// Character offset within the script, e.g., 74.
+        offset: i32,
+        /// Object owning the property, e.g., `{729551,Window}`.
+        object: JSValueRef<'a>,
+        /// Property name/index, e.g., `"cdp"`.
+        property: JSValueRef<'a>,
+    },
+
+    /// `s`: Setting property value, e.g., `foo.bar = baz`.
+    SetProperty {
+        /// Character offset within the script, e.g., 185.
+        offset: i32,
+        /// Object owning the property, e.g., `{729551,Window}`.
+        object: JSValueRef<'a>,
+        /// Property name/index, e.g., `"cdp"`.
+        property: JSValueRef<'a>,
+        /// New value, e.g., `{663864,Object}`.
+        value: JSValueRef<'a>,
+    },
+}
+
 /// Unsure script ID (`?` in the log file).
 pub const ID_UNSURE: i32 = i32::MIN;
 
@@ -92,6 +176,14 @@ impl TryFrom<&str> for LogRecord {
     type Error = LogRecordErr;
 
     fn try_from(line: &str) -> Result<Self, Self::Error> {
+        LogRecordRef::try_from(line).map(LogRecordRef::into_owned)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for LogRecordRef<'a> {
+    type Error = LogRecordErr;
+
+    fn try_from(line: &'a str) -> Result<Self, Self::Error> {
         let mut parts = SplitRecordLine::new(&line[1..]);
         match &line[..1] {
             "~" => {
@@ -101,12 +193,12 @@ impl TryFrom<&str> for LogRecord {
                 }
                 let address = i64::from_str_radix(&address_str[2..], 16)
                     .map_err(|_| LogRecordErr::InvalidHexNumber)?;
-                Ok(LogRecord::IsolateContext { address })
+                Ok(LogRecordRef::IsolateContext { address })
             }
 
             "@" => {
                 let value = parts.next().ok_or(LogRecordErr::NoValue)?.into();
-                Ok(LogRecord::WindowOrigin { value })
+                Ok(LogRecordRef::WindowOrigin { value })
             }
 
             "$" => {
@@ -116,8 +208,8 @@ impl TryFrom<&str> for LogRecord {
                     .parse()
                     .map_err(|_| LogRecordErr::InvalidScriptId)?;
                 let name = parts.next().ok_or(LogRecordErr::NoScriptName)?.into();
-                let source = unescape_colon(parts.drain());
-                Ok(LogRecord::ScriptProvenance { id, name, source })
+                let source = unescape(parts.drain());
+                Ok(LogRecordRef::ScriptProvenance { id, name, source })
             }
 
             "!" => {
@@ -130,7 +222,7 @@ impl TryFrom<&str> for LogRecord {
                         .parse()
                         .map_err(|_| LogRecordErr::InvalidExecutionContextScriptId)?,
                 };
-                Ok(LogRecord::ExecutionContext { script_id })
+                Ok(LogRecordRef::ExecutionContext { script_id })
             }
 
             "c" => {
@@ -149,7 +241,7 @@ impl TryFrom<&str> for LogRecord {
                     .ok_or(LogRecordErr::NoFunctionCallReceiver)?
                     .into();
                 let arguments = parts.map(Into::into).collect();
-                Ok(LogRecord::FunctionCall {
+                Ok(LogRecordRef::FunctionCall {
                     offset,
                     method,
                     is_user_fn,
@@ -170,7 +262,7 @@ impl TryFrom<&str> for LogRecord {
                     None => (method_w_prefix.into(), true),
                 };
                 let arguments = parts.map(Into::into).collect();
-                Ok(LogRecord::ConstructionCall {
+                Ok(LogRecordRef::ConstructionCall {
                     offset,
                     method,
                     is_user_fn,
@@ -192,7 +284,7 @@ impl TryFrom<&str> for LogRecord {
                     .next()
                     .ok_or(LogRecordErr::NoGetPropertyProperty)?
                     .into();
-                Ok(LogRecord::GetProperty {
+                Ok(LogRecordRef::GetProperty {
                     offset,
                     object,
                     property,
@@ -214,7 +306,7 @@ impl TryFrom<&str> for LogRecord {
                     .ok_or(LogRecordErr::NoSetPropertyProperty)?
                     .into();
                 let value = parts.next().ok_or(LogRecordErr::NoSetPropertyValue)?.into();
-                Ok(LogRecord::SetProperty {
+                Ok(LogRecordRef::SetProperty {
                     offset,
                     object,
                     property,
@@ -226,6 +318,130 @@ impl TryFrom<&str> for LogRecord {
     }
 }
 
+impl LogRecordRef<'_> {
+    /// Convert to [LogRecord] by allocating the borrowed strings.
+    pub fn into_owned(self) -> LogRecord {
+        match self {
+            Self::IsolateContext { address } => LogRecord::IsolateContext { address },
+            Self::WindowOrigin { value } => LogRecord::WindowOrigin {
+                value: value.into_owned(),
+            },
+            Self::ScriptProvenance { id, name, source } => LogRecord::ScriptProvenance {
+                id,
+                name: name.into_owned(),
+                source: source.into_owned(),
+            },
+            Self::ExecutionContext { script_id } => LogRecord::ExecutionContext { script_id },
+            Self::FunctionCall {
+                offset,
+                method,
+                is_user_fn,
+                receiver,
+                arguments,
+            } => LogRecord::FunctionCall {
+                offset,
+                method: method.into_owned(),
+                is_user_fn,
+                receiver: receiver.into_owned(),
+                arguments: arguments.into_iter().map(JSValueRef::into_owned).collect(),
+            },
+            Self::ConstructionCall {
+                offset,
+                method,
+                is_user_fn,
+                arguments,
+            } => LogRecord::ConstructionCall {
+                offset,
+                method: method.into_owned(),
+                is_user_fn,
+                arguments: arguments.into_iter().map(JSValueRef::into_owned).collect(),
+            },
+            Self::GetProperty {
+                offset,
+                object,
+                property,
+            } => LogRecord::GetProperty {
+                offset,
+                object: object.into_owned(),
+                property: property.into_owned(),
+            },
+            Self::SetProperty {
+                offset,
+                object,
+                property,
+                value,
+            } => LogRecord::SetProperty {
+                offset,
+                object: object.into_owned(),
+                property: property.into_owned(),
+                value: value.into_owned(),
+            },
+        }
+    }
+}
+
+impl From<LogRecord> for LogRecordRef<'static> {
+    /// Wrap the owned strings without copying them.
+    fn from(record: LogRecord) -> Self {
+        match record {
+            LogRecord::IsolateContext { address } => Self::IsolateContext { address },
+            LogRecord::WindowOrigin { value } => Self::WindowOrigin {
+                value: value.into(),
+            },
+            LogRecord::ScriptProvenance { id, name, source } => Self::ScriptProvenance {
+                id,
+                name: name.into(),
+                source: source.into(),
+            },
+            LogRecord::ExecutionContext { script_id } => Self::ExecutionContext { script_id },
+            LogRecord::FunctionCall {
+                offset,
+                method,
+                is_user_fn,
+                receiver,
+                arguments,
+            } => Self::FunctionCall {
+                offset,
+                method: method.into(),
+                is_user_fn,
+                receiver: receiver.into(),
+                arguments: arguments.into_iter().map(Into::into).collect(),
+            },
+            LogRecord::ConstructionCall {
+                offset,
+                method,
+                is_user_fn,
+                arguments,
+            } => Self::ConstructionCall {
+                offset,
+                method: method.into(),
+                is_user_fn,
+                arguments: arguments.into_iter().map(Into::into).collect(),
+            },
+            LogRecord::GetProperty {
+                offset,
+                object,
+                property,
+            } => Self::GetProperty {
+                offset,
+                object: object.into(),
+                property: property.into(),
+            },
+            LogRecord::SetProperty {
+                offset,
+                object,
+                property,
+                value,
+            } => Self::SetProperty {
+                offset,
+                object: object.into(),
+                property: property.into(),
+                value: value.into(),
+            },
+        }
+    }
+}
+
 /// Error when parsing a line of VV8 log record.
 #[derive(Error)]
 #[derive_enum_everything]
@@ -147,3 +147,68 @@ fn log_record_parsing() {
     let actual = r#"g219612:{453703,HTMLDocument}:""#.try_into().unwrap();
     assert_eq!(expected, actual);
 }
+
+#[test]
+fn escaped_string_decoding() {
+    assert_eq!(unescape(r"plain"), "plain");
+    assert_eq!(unescape(r"a\:b\\c"), r"a:b\c");
+    assert_eq!(unescape(r"caf\xe9\x0a"), "café\n");
+    assert_eq!(unescape(r"\u5708 \u2b55"), "\u{5708} \u{2b55}");
+    assert_eq!(unescape(r"\ud83d\ude00!"), "\u{1f600}!");
+    // Malformed escapes are kept verbatim.
+    assert_eq!(unescape(r"\ud83d!"), r"\ud83d!");
+    assert_eq!(unescape(r"\ude00\xZZ\q\"), r"\ude00\xZZ\q\");
+    assert_eq!(unescape(r"\\x41"), r"\x41");
+
+    let expected = LogRecord::ScriptProvenance {
+        id: 9,
+        name: JSValue::String("https://example.com/caf\u{e9}.js".into()),
+        source: "var s = \"\u{5708}\u{1f600}\";\nconsole.log(s)".into(),
+    };
+    let line =
+        r#"$9:"https\://example.com/caf\xe9.js":var s = "\u5708\ud83d\ude00";\x0aconsole.log(s)"#;
+    let actual = line.try_into().unwrap();
+    assert_eq!(expected, actual);
+
+    let expected = LogRecord::SetProperty {
+        offset: 8,
+        object: JSValue::Object {
+            index: 667758,
+            constructor: "HTMLDivElement".into(),
+        },
+        property: JSValue::String("textContent".into()),
+        value: JSValue::String("\u{a9} 2024".into()),
+    };
+    let actual = r#"s8:{667758,HTMLDivElement}:"textContent":"\xa9 2024""#
+        .try_into()
+        .unwrap();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn borrowed_log_record_parsing() {
+    let line = r#"c326104:%createPolicy:{551471,TrustedTypePolicyFactory}:"polymer_resin":"a\:b""#;
+    let record = LogRecordRef::try_from(line).unwrap();
+    let LogRecordRef::FunctionCall {
+        method,
+        receiver: JSValueRef::Object { constructor, .. },
+        arguments,
+        ..
+    } = &record
+    else {
+        panic!("Unexpected record: {record:?}");
+    };
+    assert!(matches!(method, Cow::Borrowed("createPolicy")));
+    assert!(matches!(
+        constructor,
+        Cow::Borrowed("TrustedTypePolicyFactory")
+    ));
+    assert!(matches!(
+        arguments[..],
+        [
+            JSValueRef::String(Cow::Borrowed("polymer_resin")),
+            JSValueRef::String(Cow::Owned(_))
+        ]
+    ));
+    assert_eq!(record.into_owned(), LogRecord::try_from(line).unwrap());
+}
@@ -0,0 +1,203 @@
+use super::*;
+
+/// One logical log of a thread, which VV8 may split into multiple
+/// [LogFile]s with thread names `chrome.0`, `chrome.1`, `chrome.2`, etc.
+/// Later parts continue the earlier ones, so
+/// scripts declared in an earlier part may execute in a later part.
+#[derive_float_everything]
+#[pub_fields]
+pub struct StitchedLog {
+    /// The process ID shared by all parts.
+    pid: u32,
+    /// The thread ID shared by all parts.
+    tid: u32,
+    /// The parts, ordered by the numeric suffix in their thread name,
+    /// then their timestamp.
+    parts: Vec<LogFile>,
+}
+
+impl StitchedLog {
+    /// Group `logs` by `(pid, tid)` into stitched logs,
+    /// sorted by `(pid, tid)`.
+    pub fn stitch(logs: impl IntoIterator<Item = LogFile>) -> Vec<Self> {
+        group_parts(logs, |log| &log.info)
+            .into_iter()
+            .map(|((pid, tid), parts)| Self { pid, tid, parts })
+            .collect()
+    }
+
+    /// All records of all parts in order, with line numbers continuing
+    /// across parts (see [StitchedLog::locate]).
+    pub fn records(&self) -> impl Iterator<Item = (usize, &LogRecord)> {
+        self.parts
+            .iter()
+            .zip(self.line_offsets())
+            .flat_map(|(part, line_offset)| {
+                part.records
+                    .iter()
+                    .map(move |(line, record)| (line_offset + line, record))
+            })
+    }
+
+    /// Owned version of [StitchedLog::records], e.g.,
+    /// for feeding [RecordAggregate::add].
+    pub fn into_records(self) -> impl Iterator<Item = (usize, LogRecord)> {
+        let line_offsets: Vec<_> = self.line_offsets().collect();
+        self.parts
+            .into_iter()
+            .zip(line_offsets)
+            .flat_map(|(part, line_offset)| {
+                part.records
+                    .into_iter()
+                    .map(move |(line, record)| (line_offset + line, record))
+            })
+    }
+
+    /// Map a continued line number back to the part it is in and
+    /// the line number within that part.
+    pub fn locate(&self, line: usize) -> Option<(&LogFileInfo, usize)> {
+        let mut line_offset = 0;
+        for part in &self.parts {
+            let n_lines = part.n_lines();
+            if line < line_offset + n_lines {
+                return Some((&part.info, line - line_offset));
+            }
+            line_offset += n_lines;
+        }
+        None
+    }
+
+    /// The continued line number each part starts at.
+    fn line_offsets(&self) -> impl Iterator<Item = usize> + '_ {
+        self.parts.iter().scan(0, |line_offset, part| {
+            let start = *line_offset;
+            *line_offset += part.n_lines();
+            Some(start)
+        })
+    }
+}
+
+/// Streaming counterpart of [StitchedLog] that chains the
+/// [LogStream]s of its parts, reading each part only once the previous
+/// ones are exhausted, so a multi-part log is never loaded as a whole.
+/// Yields records like [LogStream], with line numbers continuing
+/// across parts.
+pub struct StitchedStream<R = BufReader<Box<dyn Read + Send>>> {
+    pid: u32,
+    tid: u32,
+    /// The parts not exhausted yet, in order.
+    parts: VecDeque<LogStream<R>>,
+    /// The continued line number the first part in `parts` starts at.
+    part_start: usize,
+    /// Each exhausted part with the continued line numbers
+    /// it starts and ends at.
+    read_parts: Vec<(LogFileInfo, usize, usize)>,
+}
+
+impl<R: BufRead> StitchedStream<R> {
+    /// Group `streams` by `(pid, tid)` like [StitchedLog::stitch].
+    /// This collects `streams`, e.g., opening every file from
+    /// [stream_logs], but reads none of them.
+    pub fn stitch(streams: impl IntoIterator<Item = LogStream<R>>) -> Vec<Self> {
+        group_parts(streams, LogStream::info)
+            .into_iter()
+            .map(|((pid, tid), parts)| Self {
+                pid,
+                tid,
+                parts: parts.into(),
+                part_start: 0,
+                read_parts: Vec::new(),
+            })
+            .collect()
+    }
+
+    /// The process ID shared by all parts.
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// The thread ID shared by all parts.
+    pub fn tid(&self) -> u32 {
+        self.tid
+    }
+
+    /// Like [LogStream::next_ref], moving on to the next part
+    /// when one is exhausted.
+    pub fn next_ref(&mut self) -> Option<Result<(usize, LogRecordRef<'_>), ReadErr>> {
+        let line_n = loop {
+            match self.parts.front_mut()?.read_line() {
+                Some(Ok(line_n)) => break line_n,
+                Some(Err(err)) => return Some(Err(err)),
+                None => self.next_part(),
+            }
+        };
+        Some(self.parts.front()?.parse_line(line_n))
+    }
+
+    /// Like [StitchedLog::locate], but only for the lines read so far.
+    pub fn locate(&self, line: usize) -> Option<(&LogFileInfo, usize)> {
+        let current = self
+            .parts
+            .front()
+            .map(|part| (part.info(), self.part_start, part.line_n()));
+        self.read_parts
+            .iter()
+            .map(|(info, start, end)| (info, *start, *end))
+            .chain(current)
+            .find(|&(_, start, end)| start <= line && line < end)
+            .map(|(info, start, _)| (info, line - start))
+    }
+
+    /// Drop the exhausted first part and continue its line numbers
+    /// in the next one.
+    fn next_part(&mut self) {
+        let Some(part) = self.parts.pop_front() else {
+            return;
+        };
+        let end = part.line_n();
+        self.read_parts
+            .push((part.info().clone(), self.part_start, end));
+        self.part_start = end;
+        if let Some(next_part) = self.parts.front_mut() {
+            next_part.continue_from(end);
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for StitchedStream<R> {
+    type Item = Result<(usize, LogRecord), ReadErr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_ref()
+            .map(|result| result.map(|(line_n, record)| (line_n, record.into_owned())))
+    }
+}
+
+/// Group `parts` by `(pid, tid)`, sorted by `(pid, tid)`, ordering the
+/// parts of each group by the numeric suffix in their thread name,
+/// then their timestamp.
+fn group_parts<T>(
+    parts: impl IntoIterator<Item = T>,
+    info: fn(&T) -> &LogFileInfo,
+) -> Vec<((u32, u32), Vec<T>)> {
+    let mut groups = HashMap::<(u32, u32), Vec<T>>::new();
+    for part in parts {
+        let part_info = info(&part);
+        groups
+            .entry((part_info.pid, part_info.tid))
+            .or_default()
+            .push(part);
+    }
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    for (_, parts) in &mut groups {
+        parts.sort_unstable_by_key(|part| {
+            let part_info = info(part);
+            (part_info.thread_index().unwrap_or(0), part_info.timestamp)
+        });
+    }
+    groups.sort_unstable_by_key(|(pid_tid, _)| *pid_tid);
+    groups
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,100 @@
+use super::*;
+
+fn info(timestamp: u64, pid: u32, thread_name: &str) -> LogFileInfo {
+    LogFileInfo {
+        timestamp,
+        pid,
+        tid: pid,
+        thread_name: thread_name.into(),
+    }
+}
+
+fn log_file(timestamp: u64, pid: u32, thread_name: &str, lines: &[&str]) -> LogFile {
+    LogFile::from_reader(
+        info(timestamp, pid, thread_name),
+        lines.join("\n").as_bytes(),
+    )
+}
+
+#[test]
+fn stitch_continuation_logs() {
+    let logs = vec![
+        log_file(3, 87, "chrome.1", &["!5", r#"c9:%atob:{1,Window}:"""#]),
+        log_file(2, 90, "chrome.0", &["~0x1"]),
+        log_file(
+            1,
+            87,
+            "chrome.0",
+            &["~0x1", r#"$5:"https\://a.com/a.js":atob("")"#],
+        ),
+    ];
+    let stitched = StitchedLog::stitch(logs);
+    assert_eq!(stitched.len(), 2);
+    let log = &stitched[0];
+    assert_eq!((log.pid, log.tid), (87, 87));
+    let thread_names: Vec<_> = log
+        .parts
+        .iter()
+        .map(|part| &part.info.thread_name[..])
+        .collect();
+    assert_eq!(thread_names, ["chrome.0", "chrome.1"]);
+
+    let lines: Vec<_> = log.records().map(|(line, _)| line).collect();
+    assert_eq!(lines, [0, 1, 2, 3]);
+    let (info, line) = log.locate(3).unwrap();
+    assert_eq!((&info.thread_name[..], line), ("chrome.1", 1));
+    assert!(log.locate(4).is_none());
+
+    let mut aggregate = RecordAggregate::default();
+    for (line, record) in stitched.into_iter().next().unwrap().into_records() {
+        aggregate.add(line as u32, record).unwrap();
+    }
+    let script = aggregate.scripts.values().next().unwrap();
+    assert_eq!(script.api_calls.len(), 1);
+}
+
+#[test]
+fn stitch_continuation_streams() {
+    let streams = vec![
+        LogStream::new(
+            info(3, 87, "chrome.1"),
+            "!5\nbad line\nc9:%atob:{1,Window}:\"\"\n".as_bytes(),
+        ),
+        LogStream::new(info(2, 90, "chrome.0"), "~0x1\n".as_bytes()),
+        LogStream::new(
+            info(1, 87, "chrome.0"),
+            "~0x1\n$5:\"https\\://a.com/a.js\":atob(\"\")\n".as_bytes(),
+        ),
+    ];
+    let mut stitched = StitchedStream::stitch(streams);
+    assert_eq!(stitched.len(), 2);
+    let stream = &mut stitched[0];
+    assert_eq!((stream.pid(), stream.tid()), (87, 87));
+
+    let mut aggregate = RecordAggregate::default();
+    let mut lines = Vec::new();
+    let mut err_lines = Vec::new();
+    while let Some(result) = stream.next_ref() {
+        match result {
+            Ok((line, record)) => {
+                lines.push(line);
+                aggregate.add(line as u32, record).unwrap();
+            }
+            Err(err) => err_lines.push(err),
+        }
+    }
+    assert_eq!(lines, [0, 1, 2, 4]);
+    assert_eq!(err_lines.len(), 1);
+    let script = aggregate.scripts.values().next().unwrap();
+    assert_eq!(script.api_calls.len(), 1);
+    assert_eq!(script.api_calls.values().next().unwrap().lines, [4]);
+
+    let (info, line) = stream.locate(3).unwrap();
+    assert_eq!((&info.thread_name[..], line), ("chrome.1", 1));
+    let (info, line) = stream.locate(1).unwrap();
+    assert_eq!((&info.thread_name[..], line), ("chrome.0", 1));
+    assert!(stream.locate(5).is_none());
+
+    let records: Vec<_> = stitched.pop().unwrap().collect();
+    assert_eq!(records.len(), 1);
+}
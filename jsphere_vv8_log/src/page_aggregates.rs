@@ -0,0 +1,263 @@
+use super::*;
+
+/// All scripts a page load ran, merged from the [RecordAggregate]s of
+/// every log of a trial, e.g., the renderer's, workers', and
+/// other processes'.
+#[pub_fields]
+#[derive(Clone, Debug, Default)]
+pub struct PageAggregate {
+    /// One aggregate per [StitchedLog] or [StitchedStream], in the order given.
+    aggregates: Vec<RecordAggregate>,
+    /// Distinct page scripts, in the order they first appear.
+    /// Injected scripts are excluded.
+    scripts: Vec<PageScript>,
+    /// Number of records that failed to aggregate.
+    n_err: usize,
+}
+
+impl PageAggregate {
+    /// Stream, stitch, and aggregate all logs in the trial directory `dir`
+    /// with default [RecordAggregate] settings and [SphereRules].
+    pub fn read<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let streams = StitchedStream::stitch(stream_logs(dir)?);
+        Ok(Self::from_streams(
+            streams,
+            &RecordAggregate::default(),
+            SphereRules::default_rules(),
+        ))
+    }
+
+    /// Aggregate `logs`, each starting from a clone of `template`,
+    /// which should carry the settings but no records,
+    /// and classify the scripts with `rules`.
+    pub fn from_logs(
+        logs: Vec<StitchedLog>,
+        template: &RecordAggregate,
+        rules: &SphereRules,
+    ) -> Self {
+        Self::from_each(logs, template, rules, |log, aggregate| {
+            let (pid, tid) = (log.pid, log.tid);
+            let mut n_err = 0;
+            for (line, record) in log.into_records() {
+                if let Err(err) = aggregate.add(line as u32, record) {
+                    debug!(pid, tid, line, ?err, "Aggregating record");
+                    n_err += 1;
+                }
+            }
+            n_err
+        })
+    }
+
+    /// Like [PageAggregate::from_logs], but reading each log as it is
+    /// aggregated. Records that fail to read are skipped and not counted
+    /// in [PageAggregate::n_err].
+    pub fn from_streams(
+        streams: Vec<StitchedStream>,
+        template: &RecordAggregate,
+        rules: &SphereRules,
+    ) -> Self {
+        Self::from_each(streams, template, rules, |mut stream, aggregate| {
+            let (pid, tid) = (stream.pid(), stream.tid());
+            let mut n_err = 0;
+            while let Some(result) = stream.next_ref() {
+                let Ok((line, record)) = result else {
+                    continue;
+                };
+                if let Err(err) = aggregate.add(line as u32, record) {
+                    debug!(pid, tid, line, ?err, "Aggregating record");
+                    n_err += 1;
+                }
+            }
+            n_err
+        })
+    }
+
+    /// Aggregate each of `logs` in parallel into a clone of `template`
+    /// with `add_records`, which returns the number of errors.
+    fn from_each<L: Send>(
+        logs: Vec<L>,
+        template: &RecordAggregate,
+        rules: &SphereRules,
+        add_records: impl Fn(L, &mut RecordAggregate) -> usize + Sync + Send,
+    ) -> Self {
+        let (aggregates, n_errs): (Vec<_>, Vec<_>) = logs
+            .into_par_iter()
+            .map(|log| {
+                let mut aggregate = template.clone();
+                let n_err = add_records(log, &mut aggregate);
+                (aggregate, n_err)
+            })
+            .unzip();
+        let mut page = Self::from_aggregates_with(aggregates, rules);
+        page.n_err = n_errs.into_iter().sum();
+        page
+    }
+
+    /// Deduplicate the scripts in `aggregates` by URL and content hash,
+    /// classified with the default [SphereRules].
+    pub fn from_aggregates(aggregates: Vec<RecordAggregate>) -> Self {
+        Self::from_aggregates_with(aggregates, SphereRules::default_rules())
+    }
+
+    /// [PageAggregate::from_aggregates] classified with `rules`.
+    pub fn from_aggregates_with(aggregates: Vec<RecordAggregate>, rules: &SphereRules) -> Self {
+        let mut scripts = Vec::<PageScript>::new();
+        let mut indexes = HashMap::<(Option<String>, ContentHash), usize>::new();
+        for (i_aggregate, aggregate) in aggregates.iter().enumerate() {
+            let tree = ScriptTree::new(aggregate);
+            let mut ids: Vec<_> = aggregate
+                .scripts
+                .iter()
+                .filter(|(_, script)| is_page_script(script))
+                .map(|(&id, script)| (script.line, id))
+                .collect();
+            ids.sort_unstable();
+            for (_, id) in ids {
+                let script = &aggregate.scripts[&id];
+                let url = match &script.name {
+                    ScriptName::Url(url) => Some(url.clone()),
+                    _ => None,
+                };
+                let content_hash = script.content_hash();
+                let spheres = rules.classify(script);
+                let i_script = *indexes
+                    .entry((url.clone(), content_hash))
+                    .or_insert_with(|| {
+                        scripts.push(PageScript {
+                            url,
+                            content_hash,
+                            size: script.size(),
+                            library: tree.library(id),
+                            ..Default::default()
+                        });
+                        scripts.len() - 1
+                    });
+                let page_script = &mut scripts[i_script];
+                page_script.spheres = page_script.spheres.union(spheres);
+                page_script.occurrences.push((i_aggregate, id));
+            }
+        }
+        Self {
+            aggregates,
+            scripts,
+            n_err: 0,
+        }
+    }
+
+    /// Total size of the distinct page scripts, see [ScriptAggregate::size].
+    pub fn total_bytes(&self) -> usize {
+        self.scripts.iter().map(|script| script.size).sum()
+    }
+
+    /// Total size of the distinct page scripts in each sphere.
+    /// A script in several spheres counts toward each.
+    pub fn sphere_bytes(&self) -> BTreeMap<Sphere, usize> {
+        let mut bytes = BTreeMap::new();
+        for script in &self.scripts {
+            for sphere in script.spheres.iter() {
+                *bytes.entry(sphere).or_default() += script.size;
+            }
+        }
+        bytes
+    }
+
+    /// Bytes sent over the wire for the page scripts served from URLs,
+    /// per [Har::response_for] and [HarEntry::transfer_size],
+    /// in total and in each sphere. Each URL counts once, in the spheres
+    /// of its scripts together with all scripts they `eval`ed, since
+    /// those came over the wire as part of the URL's response.
+    pub fn transfer_bytes(&self, har: &Har) -> (u64, BTreeMap<Sphere, u64>) {
+        let occurrence_spheres: HashMap<_, _> = self
+            .scripts
+            .iter()
+            .flat_map(|script| {
+                script
+                    .occurrences
+                    .iter()
+                    .map(|&occurrence| (occurrence, script.spheres))
+            })
+            .collect();
+        let trees: Vec<_> = self.aggregates.iter().map(ScriptTree::new).collect();
+        let mut url_spheres = HashMap::<&str, SphereSet>::new();
+        for script in &self.scripts {
+            let Some(url) = &script.url else {
+                continue;
+            };
+            let spheres = url_spheres.entry(url).or_default();
+            for &(i_aggregate, id) in &script.occurrences {
+                for descendant in trees[i_aggregate].subtree(id) {
+                    if let Some(&descendant_spheres) =
+                        occurrence_spheres.get(&(i_aggregate, descendant))
+                    {
+                        *spheres = spheres.union(descendant_spheres);
+                    }
+                }
+            }
+        }
+        let mut total = 0;
+        let mut bytes = BTreeMap::new();
+        for (url, spheres) in url_spheres {
+            let Some(size) = har.response_for(url).and_then(HarEntry::transfer_size) else {
+                continue;
+            };
+            total += size;
+            for sphere in spheres.iter() {
+                *bytes.entry(sphere).or_default() += size;
+            }
+        }
+        (total, bytes)
+    }
+
+    /// Like [PageAggregate::sphere_bytes], but only counting
+    /// scripts fingerprinted as libraries, i.e., vendored code.
+    /// The rest of each sphere is first-party application code.
+    pub fn library_sphere_bytes(&self) -> BTreeMap<Sphere, usize> {
+        let mut bytes = BTreeMap::new();
+        for script in self
+            .scripts
+            .iter()
+            .filter(|script| script.library.is_some())
+        {
+            for sphere in script.spheres.iter() {
+                *bytes.entry(sphere).or_default() += script.size;
+            }
+        }
+        bytes
+    }
+
+    /// The aggregate of an occurrence in [PageScript::occurrences].
+    pub fn script(&self, (i_aggregate, id): (usize, ScriptId)) -> Option<&ScriptAggregate> {
+        self.aggregates.get(i_aggregate)?.scripts.get(&id)
+    }
+}
+
+/// A distinct script of a page, which may have run several times.
+#[pub_fields]
+#[derive_everything]
+pub struct PageScript {
+    /// [ScriptName::Url] of the script, if any.
+    url: Option<String>,
+    content_hash: ContentHash,
+    /// [ScriptAggregate::size].
+    size: usize,
+    /// The library the first occurrence was fingerprinted as, if any;
+    /// `eval`ed scripts inherit their original script's, see
+    /// [ScriptTree::library].
+    library: Option<Library>,
+    /// Union of the spheres of all occurrences.
+    spheres: SphereSet,
+    /// Index in [PageAggregate::aggregates] and script ID of each run.
+    occurrences: Vec<(usize, ScriptId)>,
+}
+
+/// Source of the script the crawler injects in response to blocked
+/// navigations. It is not marked as injected, so filter it by source.
+pub const CRAWLER_BACK_NAVIGATION_SOURCE: &str = "window.history.back()";
+
+fn is_page_script(script: &ScriptAggregate) -> bool {
+    matches!(script.injection_type, ScriptInjectionType::Not)
+        && script.source != CRAWLER_BACK_NAVIGATION_SOURCE
+}
+
+#[cfg(test)]
+mod tests;
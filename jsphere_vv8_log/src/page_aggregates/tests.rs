@@ -0,0 +1,109 @@
+use super::*;
+
+#[test]
+fn dedup_scripts_across_logs() {
+    let renderer = RecordAggregate::from_lines(&[
+        "~0x1",
+        r#"$5:"https\://a.com/a.js":fetch("/")"#,
+        r#"$6:"https\://a.com/b.js":b()"#,
+        r#"$7:"https\://a.com/blocked":window.history.back()"#,
+        "!5",
+        r#"c1:%fetch:{1,Window}:"/""#,
+    ])
+    .unwrap();
+    let worker = RecordAggregate::from_lines(&[
+        "~0x2",
+        r#"$9:"https\://a.com/a.js":fetch("/")"#,
+        r#"$10:"https\://a.com/c.js":fetch("/")"#,
+    ])
+    .unwrap();
+    let page = PageAggregate::from_aggregates(vec![renderer, worker]);
+
+    let urls: Vec<_> = page
+        .scripts
+        .iter()
+        .map(|script| script.url.as_deref().unwrap())
+        .collect();
+    assert_eq!(
+        urls,
+        [
+            "https://a.com/a.js",
+            "https://a.com/b.js",
+            "https://a.com/c.js"
+        ]
+    );
+    // Not marked as injected, but filtered out by its source.
+    let blocked = &page.aggregates[0].scripts[&ScriptId { isolate: 1, id: 7 }];
+    assert!(matches!(blocked.injection_type, ScriptInjectionType::Not));
+    assert_eq!(blocked.source, CRAWLER_BACK_NAVIGATION_SOURCE);
+    let a = &page.scripts[0];
+    assert_eq!(
+        a.occurrences,
+        [
+            (0, ScriptId { isolate: 1, id: 5 }),
+            (1, ScriptId { isolate: 2, id: 9 })
+        ]
+    );
+    assert_eq!(page.script(a.occurrences[1]).unwrap().line, 1);
+    assert_eq!(a.spheres.iter().collect::<Vec<_>>(), [Sphere::HasRequest]);
+    assert_eq!(a.content_hash, page.scripts[2].content_hash);
+
+    assert_eq!(page.total_bytes(), 10 + 3 + 10);
+    assert_eq!(
+        page.sphere_bytes().into_iter().collect::<Vec<_>>(),
+        [(Sphere::HasRequest, 10)]
+    );
+}
+
+#[test]
+fn classify_with_custom_rules() {
+    let aggregate = RecordAggregate::from_lines(&[
+        "~0x1",
+        r#"$5:"https\://a.com/a.js":fetch("/")"#,
+        "!5",
+        r#"c1:%fetch:{1,Window}:"/""#,
+    ])
+    .unwrap();
+    let rules = SphereRules::from_toml(
+        r#"
+[[rules]]
+sphere = "UsesStorage"
+attr = "fetch"
+"#,
+    )
+    .unwrap();
+    let page = PageAggregate::from_aggregates_with(vec![aggregate], &rules);
+    assert_eq!(
+        page.scripts[0].spheres.iter().collect::<Vec<_>>(),
+        [Sphere::UsesStorage]
+    );
+}
+
+#[test]
+fn eval_children_inherit_library() {
+    let aggregate = RecordAggregate::from_lines(&[
+        "~0x1",
+        "$5:\"https\\://a.com/jquery-3.7.1.min.js\"://10 effectiveLen\\x0avar 圏 = eval(String.raw`//4 effectiveLen\\x0aa();`);",
+        "$6:5://4 effectiveLen\\x0aa();",
+        r#"$7:"https\://a.com/app.js":fetch("/")"#,
+        "!6",
+        r#"c1:%fetch:{1,Window}:"/a""#,
+        "!7",
+        r#"c1:%fetch:{1,Window}:"/""#,
+    ])
+    .unwrap();
+    let page = PageAggregate::from_aggregates(vec![aggregate]);
+
+    let child = &page.scripts[1];
+    assert_eq!(child.url, None);
+    assert_eq!(child.library.as_ref().unwrap().name, "jQuery");
+    assert!(page.scripts[2].library.is_none());
+    assert_eq!(
+        page.sphere_bytes().into_iter().collect::<Vec<_>>(),
+        [(Sphere::HasRequest, 4 + 10)]
+    );
+    assert_eq!(
+        page.library_sphere_bytes().into_iter().collect::<Vec<_>>(),
+        [(Sphere::HasRequest, 4)]
+    );
+}
@@ -0,0 +1,92 @@
+use super::*;
+
+/// The links a trial found and followed, as the crawler's `visitSite`
+/// writes to `reachable<N>.json`.
+/// Secondary pages are linked from the site's root page, and
+/// tertiary pages from the visited secondary pages.
+#[derive_everything]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[pub_fields]
+pub struct Reachable {
+    /// URLs navigated to from the root page.
+    secondary_pages: Vec<String>,
+    /// The same-site secondary pages sampled and visited.
+    secondary_visits: Vec<String>,
+    /// URLs navigated to from the secondary visits,
+    /// excluding secondary pages.
+    tertiary_pages: Vec<String>,
+    /// The same-site tertiary pages sampled and visited.
+    tertiary_visits: Vec<String>,
+}
+
+impl Reachable {
+    pub fn from_json(reachable: &str) -> Result<Self> {
+        serde_json::from_str(reachable).context("Parsing reachable JSON")
+    }
+
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let reachable = fs::read_to_string(path).context("Reading reachable file")?;
+        Self::from_json(&reachable)
+    }
+
+    /// Whether the root page reached any subpage on `site`, see
+    /// [is_same_site]. Other links do not count, since the crawler
+    /// does not visit them.
+    pub fn has_subpages(&self, site: &str) -> bool {
+        self.same_site_subpages(site).next().is_some()
+    }
+
+    /// The secondary pages on `site`, see [is_same_site].
+    pub fn same_site_subpages<'a>(&'a self, site: &'a str) -> impl Iterator<Item = &'a str> {
+        self.secondary_pages
+            .iter()
+            .map(String::as_str)
+            .filter(move |url| is_same_site(url, site))
+    }
+
+    /// All pages visited after the root page, in visit order.
+    pub fn visits(&self) -> impl Iterator<Item = &str> {
+        self.secondary_visits
+            .iter()
+            .chain(&self.tertiary_visits)
+            .map(String::as_str)
+    }
+}
+
+/// Whether `url` is on `site`, e.g., `youtube.com`, per the prefix rule of
+/// the crawler's `Subdomain.matchUrl` in `headless_browser/browser.js`:
+/// after stripping `http://` or `https://` and then `www.`, `url` starts
+/// with `site` without `www.`. This is not a host match: other subdomains
+/// of `site` do not count, while, e.g., `a.com.b.com` counts for `a.com`.
+///
+/// Unlike here, `matchUrl` keeps the scheme of `https://` URLs without
+/// `www.` and the `www.` of `http://` URLs, so it rejects such links and
+/// the crawler did not visit them; see [Reachable::secondary_visits] for
+/// the pages it did visit.
+pub fn is_same_site(url: &str, site: &str) -> bool {
+    const WWW: &str = "www.";
+    let site = site.strip_prefix(WWW).unwrap_or(site);
+    let url = url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"))
+        .unwrap_or(url);
+    url.strip_prefix(WWW).unwrap_or(url).starts_with(site)
+}
+
+impl ReachableFile {
+    pub fn read(&self) -> Result<Reachable> {
+        Reachable::read(&self.path)
+    }
+}
+
+impl Trial {
+    /// Read [Trial::reachable], or [None] if the crawler did not write it,
+    /// e.g., because the visit failed.
+    pub fn read_reachable(&self) -> Result<Option<Reachable>> {
+        self.reachable.as_ref().map(ReachableFile::read).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,73 @@
+use super::*;
+
+#[test]
+fn read_reachable() {
+    let reachable = Reachable::from_json(
+        r#"{
+	"secondaryPages": [
+		"https://a.com/1",
+		"https://m.a.com/",
+		"https://b.com/"
+	],
+	"secondaryVisits": [
+		"https://a.com/1"
+	],
+	"tertiaryPages": [
+		"https://a.com/2"
+	],
+	"tertiaryVisits": [
+		"https://a.com/2"
+	]
+}"#,
+    )
+    .unwrap();
+    assert!(reachable.has_subpages("a.com"));
+    assert_eq!(reachable.secondary_pages.len(), 3);
+    assert_eq!(
+        reachable.same_site_subpages("a.com").collect::<Vec<_>>(),
+        ["https://a.com/1"]
+    );
+    assert!(!reachable.has_subpages("c.com"));
+    assert_eq!(
+        reachable.visits().collect::<Vec<_>>(),
+        ["https://a.com/1", "https://a.com/2"]
+    );
+
+    let dead_end = Reachable::from_json(
+        r#"{"secondaryPages":[],"secondaryVisits":[],"tertiaryPages":[],"tertiaryVisits":[]}"#,
+    )
+    .unwrap();
+    assert!(!dead_end.has_subpages("a.com"));
+    assert!(Reachable::from_json("{}").is_err());
+}
+
+#[test]
+fn same_site_urls() {
+    assert!(is_same_site("https://a.com/1", "a.com"));
+    assert!(is_same_site("https://www.a.com/1", "a.com"));
+    assert!(is_same_site("http://a.com/1", "www.a.com"));
+    assert!(!is_same_site("https://ba.com/", "a.com"));
+    assert!(!is_same_site("javascript:void(0)", "a.com"));
+    // Prefix match like the crawler's, not a host match.
+    assert!(!is_same_site("https://shop.a.com/", "a.com"));
+    assert!(!is_same_site("https://shop.a.com/", "m.a.com"));
+    assert!(is_same_site("https://a.com.b.com/", "a.com"));
+    assert!(is_same_site("http://www.a.com/", "a.com"));
+}
+
+#[test]
+fn read_trial_reachable() {
+    let dir = tempfile::tempdir().unwrap();
+    let site_dir = dir.path();
+    fs::create_dir_all(site_dir.join("0")).unwrap();
+    fs::create_dir_all(site_dir.join("1")).unwrap();
+    fs::write(
+        site_dir.join("reachable0.json"),
+        r#"{"secondaryPages":["https://a.com/1"],"secondaryVisits":[],"tertiaryPages":[],"tertiaryVisits":[]}"#,
+    )
+    .unwrap();
+
+    let reachable = Trial::new(site_dir, 0).read_reachable().unwrap().unwrap();
+    assert_eq!(reachable.secondary_pages, ["https://a.com/1"]);
+    assert_eq!(Trial::new(site_dir, 1).read_reachable().unwrap(), None);
+}
@@ -7,8 +7,8 @@ pub struct SplitRecordLine<'a> {
     remaining: &'a str,
 }
 
-impl SplitRecordLine<'_> {
-    pub fn drain(&mut self) -> &str {
+impl<'a> SplitRecordLine<'a> {
+    pub fn drain(&mut self) -> &'a str {
         let output = self.remaining;
         self.remaining = "";
         output
@@ -0,0 +1,175 @@
+use super::*;
+
+/// The scripts of a [RecordAggregate] as trees of execution contexts,
+/// linking each script to the children it `eval`s, e.g.,
+/// the blocks the eval trick splits an original script into.
+#[derive(Clone, Debug)]
+pub struct ScriptTree<'a> {
+    aggregate: &'a RecordAggregate,
+    /// Children of each script, sorted by ID.
+    children: HashMap<ScriptId, Vec<ScriptId>>,
+    /// Scripts without a parent, sorted by ID.
+    roots: Vec<ScriptId>,
+}
+
+impl<'a> ScriptTree<'a> {
+    pub fn new(aggregate: &'a RecordAggregate) -> Self {
+        let mut children = HashMap::<_, Vec<_>>::new();
+        let mut roots = Vec::new();
+        for (&id, script) in &aggregate.scripts {
+            match script.parent_id(id) {
+                Some(parent_id) if aggregate.scripts.contains_key(&parent_id) => {
+                    children.entry(parent_id).or_default().push(id)
+                }
+                _ => roots.push(id),
+            }
+        }
+        for ids in children.values_mut() {
+            ids.sort_unstable();
+        }
+        roots.sort_unstable();
+        Self {
+            aggregate,
+            children,
+            roots,
+        }
+    }
+
+    /// Original scripts, i.e., those not `eval`ed by another script.
+    pub fn roots(&self) -> &[ScriptId] {
+        &self.roots
+    }
+
+    /// Scripts directly `eval`ed by the script `id`.
+    pub fn children(&self, id: ScriptId) -> &[ScriptId] {
+        self.children.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn parent(&self, id: ScriptId) -> Option<ScriptId> {
+        let parent_id = self.aggregate.scripts.get(&id)?.parent_id(id)?;
+        self.aggregate
+            .scripts
+            .contains_key(&parent_id)
+            .then_some(parent_id)
+    }
+
+    /// The original script the script `id` descends from, or itself.
+    pub fn root_of(&self, mut id: ScriptId) -> ScriptId {
+        while let Some(parent_id) = self.parent(id) {
+            id = parent_id;
+        }
+        id
+    }
+
+    /// The library the script `id` is, per [fingerprint], or else the
+    /// library of its original script, e.g., for the blocks the eval trick
+    /// splits a library into.
+    pub fn library(&self, id: ScriptId) -> Option<Library> {
+        let script = self.aggregate.scripts.get(&id)?;
+        fingerprint(script).or_else(|| {
+            let root_id = self.root_of(id);
+            (root_id != id)
+                .then(|| fingerprint(&self.aggregate.scripts[&root_id]))
+                .flatten()
+        })
+    }
+
+    /// Number of `eval`s between the script `id` and its original script.
+    pub fn depth(&self, mut id: ScriptId) -> usize {
+        let mut depth = 0;
+        while let Some(parent_id) = self.parent(id) {
+            id = parent_id;
+            depth += 1;
+        }
+        depth
+    }
+
+    /// The script `id` and all scripts it `eval`ed, recursively,
+    /// in depth-first order.
+    pub fn subtree(&self, id: ScriptId) -> Vec<ScriptId> {
+        let mut ids = Vec::new();
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            ids.push(id);
+            stack.extend(self.children(id).iter().rev());
+        }
+        ids
+    }
+
+    /// Aggregate the script `id` together with all its descendants,
+    /// classified with the default [SphereRules].
+    pub fn rollup(&self, id: ScriptId) -> Result<RolledUpScript> {
+        self.rollup_with(id, SphereRules::default_rules())
+    }
+
+    /// [ScriptTree::rollup] classified with `rules`.
+    pub fn rollup_with(&self, id: ScriptId, rules: &SphereRules) -> Result<RolledUpScript> {
+        let root = self
+            .aggregate
+            .scripts
+            .get(&id)
+            .context("Unknown script ID")?;
+        let mut script = ScriptAggregate {
+            api_calls: HashMap::new(),
+            n_filtered_call: 0,
+            ..root.clone()
+        };
+        let mut n_context = 0;
+        let mut total_size = 0;
+        for descendant in self.subtree(id) {
+            let descendant = &self.aggregate.scripts[&descendant];
+            n_context += 1;
+            total_size += descendant.size();
+            script.n_filtered_call += descendant.n_filtered_call;
+            for (api_call, lines) in &descendant.api_calls {
+                match script.api_calls.get_mut(api_call) {
+                    Some(merged) => merged.merge(lines)?,
+                    None => {
+                        script.api_calls.insert(api_call.clone(), lines.clone());
+                    }
+                }
+            }
+        }
+        let spheres = rules.classify(&script);
+        Ok(RolledUpScript {
+            id,
+            script,
+            n_context,
+            total_size,
+            spheres,
+        })
+    }
+
+    /// [ScriptTree::rollup] for each original script.
+    pub fn rollup_roots(&self) -> Result<Vec<RolledUpScript>> {
+        self.rollup_roots_with(SphereRules::default_rules())
+    }
+
+    /// [ScriptTree::rollup_with] for each original script.
+    pub fn rollup_roots_with(&self, rules: &SphereRules) -> Result<Vec<RolledUpScript>> {
+        self.roots
+            .iter()
+            .map(|&id| self.rollup_with(id, rules))
+            .collect()
+    }
+}
+
+/// An original script with the API calls of all its descendants.
+#[pub_fields]
+#[derive(Clone, Debug)]
+pub struct RolledUpScript {
+    id: ScriptId,
+    /// The original script, with `api_calls` and `n_filtered_call`
+    /// merged from all its descendants.
+    script: ScriptAggregate,
+    /// Number of execution contexts in the subtree, including the root.
+    n_context: usize,
+    /// Sum of [ScriptAggregate::size] in the subtree. With the eval trick,
+    /// this is the original script's size; otherwise, an `eval`ed child's
+    /// source may also be counted as part of its parent's source.
+    total_size: usize,
+    spheres: SphereSet,
+}
+
+#[cfg(test)]
+mod tests;
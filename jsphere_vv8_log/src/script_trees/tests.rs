@@ -0,0 +1,59 @@
+use super::*;
+
+fn id(id: i32) -> ScriptId {
+    ScriptId { isolate: 1, id }
+}
+
+#[test]
+fn eval_children_roll_up() {
+    let aggregate = RecordAggregate::from_lines(&[
+        "~0x1",
+        r#"$5:"https\://a.com/a.js":eval(block1);eval(block2)"#,
+        r#"$6:5:block1"#,
+        r#"$7:5:block2"#,
+        r#"$8:7:localStorage"#,
+        r#"$9:"https\://a.com/b.js":fetch()"#,
+        "!8",
+        r#"c3:%getItem:{2,Storage}:"k""#,
+        "!6",
+        r#"c1:%fetch:{1,Window}:"/""#,
+        "!5",
+        r#"c9:%fetch:{1,Window}:"/a""#,
+    ])
+    .unwrap();
+    let tree = ScriptTree::new(&aggregate);
+    assert_eq!(tree.roots(), &[id(5), id(9)]);
+    assert_eq!(tree.children(id(5)), &[id(6), id(7)]);
+    assert_eq!(tree.parent(id(8)), Some(id(7)));
+    assert_eq!(tree.root_of(id(8)), id(5));
+    assert_eq!(tree.depth(id(8)), 2);
+    assert_eq!(tree.subtree(id(5)), [id(5), id(6), id(7), id(8)]);
+
+    let rolled_up = tree.rollup(id(5)).unwrap();
+    assert_eq!(rolled_up.n_context, 4);
+    assert_eq!(
+        rolled_up.script.name,
+        ScriptName::Url("https://a.com/a.js".into())
+    );
+    let fetch = &rolled_up.script.api_calls[&ApiCall {
+        api_type: ApiType::Function,
+        this: "Window".into(),
+        attr: Some("fetch".into()),
+        ..Default::default()
+    }];
+    assert_eq!(fetch.lines, [9, 11]);
+    assert_eq!(fetch.offsets, [1, 9]);
+    assert!(rolled_up.spheres.contains(Sphere::HasRequest));
+    assert!(rolled_up.spheres.contains(Sphere::UsesStorage));
+    assert!(!tree
+        .rollup(id(9))
+        .unwrap()
+        .spheres
+        .contains(Sphere::UsesStorage));
+    assert!(tree.rollup(id(42)).is_err());
+    assert!(tree
+        .rollup_roots_with(&SphereRules::default())
+        .unwrap()
+        .iter()
+        .all(|rolled_up| rolled_up.spheres.is_empty()));
+}
@@ -0,0 +1,142 @@
+use super::*;
+
+/// The same scripts lined up across the trials of a site, e.g.,
+/// the crawler's trials `0..5`, to tell stable API calls and spheres apart
+/// from flaky ones that only show up in some trials.
+#[pub_fields]
+#[derive(Clone, Debug, Default)]
+pub struct TrialConsistency {
+    n_trial: usize,
+    /// Scripts in the order they first appear across trials.
+    scripts: Vec<ScriptConsistency>,
+}
+
+impl TrialConsistency {
+    /// Match scripts across `trials` by URL, or by content hash for
+    /// scripts without a URL.
+    pub fn new(trials: &[PageAggregate]) -> Self {
+        let mut scripts = Vec::<ScriptConsistency>::new();
+        let mut indexes = HashMap::<TrialScriptKey, usize>::new();
+        for (trial, page) in trials.iter().enumerate() {
+            for page_script in &page.scripts {
+                let key = TrialScriptKey::of(page_script);
+                let i_script = *indexes.entry(key.clone()).or_insert_with(|| {
+                    scripts.push(ScriptConsistency {
+                        key,
+                        ..Default::default()
+                    });
+                    scripts.len() - 1
+                });
+                let script = &mut scripts[i_script];
+                // A URL may serve different sources within a trial;
+                // count each trial once.
+                let first_in_trial = script.trials.last() != Some(&trial);
+                if first_in_trial {
+                    script.trials.push(trial);
+                }
+                let api_calls: HashSet<_> = page_script
+                    .occurrences
+                    .iter()
+                    .filter_map(|&occurrence| page.script(occurrence))
+                    .flat_map(|aggregate| aggregate.api_calls.keys())
+                    .collect();
+                for api_call in api_calls {
+                    let trials = script.api_calls.entry(api_call.clone()).or_default();
+                    if trials.last() != Some(&trial) {
+                        trials.push(trial);
+                    }
+                }
+                for sphere in page_script.spheres.iter() {
+                    let trials = script.spheres.entry(sphere).or_default();
+                    if trials.last() != Some(&trial) {
+                        trials.push(trial);
+                    }
+                }
+            }
+        }
+        Self {
+            n_trial: trials.len(),
+            scripts,
+        }
+    }
+
+    /// Scripts that ran in every trial.
+    pub fn scripts_in_all_trials(&self) -> impl Iterator<Item = &ScriptConsistency> {
+        self.scripts
+            .iter()
+            .filter(|script| script.trials.len() == self.n_trial)
+    }
+}
+
+/// How a script is matched across trials.
+#[derive_enum_everything]
+pub enum TrialScriptKey {
+    Url(String),
+    ContentHash(ContentHash),
+}
+
+impl TrialScriptKey {
+    pub fn of(script: &PageScript) -> Self {
+        match &script.url {
+            Some(url) => Self::Url(url.clone()),
+            None => Self::ContentHash(script.content_hash),
+        }
+    }
+}
+
+impl Default for TrialScriptKey {
+    fn default() -> Self {
+        Self::ContentHash(ContentHash::default())
+    }
+}
+
+/// A script across trials. "Stable" means present in every trial
+/// the script ran in, and "flaky" means present in only some of them.
+#[pub_fields]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ScriptConsistency {
+    key: TrialScriptKey,
+    /// Trials the script ran in, ascending.
+    trials: Vec<usize>,
+    /// Trials each API call was made in, ascending.
+    api_calls: HashMap<ApiCall, Vec<usize>>,
+    /// Trials the script was classified into each sphere in, ascending.
+    spheres: BTreeMap<Sphere, Vec<usize>>,
+}
+
+impl ScriptConsistency {
+    pub fn stable_api_calls(&self) -> impl Iterator<Item = &ApiCall> {
+        self.api_calls
+            .iter()
+            .filter(|(_, trials)| trials.len() == self.trials.len())
+            .map(|(api_call, _)| api_call)
+    }
+
+    pub fn flaky_api_calls(&self) -> impl Iterator<Item = &ApiCall> {
+        self.api_calls
+            .iter()
+            .filter(|(_, trials)| trials.len() < self.trials.len())
+            .map(|(api_call, _)| api_call)
+    }
+
+    /// Spheres the script was classified into in every trial it ran in,
+    /// i.e., its deterministic classification.
+    pub fn stable_spheres(&self) -> SphereSet {
+        self.spheres
+            .iter()
+            .filter(|(_, trials)| trials.len() == self.trials.len())
+            .map(|(&sphere, _)| sphere)
+            .collect()
+    }
+
+    pub fn flaky_spheres(&self) -> SphereSet {
+        self.spheres
+            .iter()
+            .filter(|(_, trials)| trials.len() < self.trials.len())
+            .map(|(&sphere, _)| sphere)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests;
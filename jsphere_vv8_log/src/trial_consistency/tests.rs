@@ -0,0 +1,51 @@
+use super::*;
+
+fn page(lines: &[&str]) -> PageAggregate {
+    PageAggregate::from_aggregates(vec![RecordAggregate::from_lines(lines).unwrap()])
+}
+
+#[test]
+fn stable_and_flaky_across_trials() {
+    let trials = [
+        page(&[
+            "~0x1",
+            r#"$5:"https\://a.com/a.js":a()"#,
+            r#"$6:"https\://a.com/ads.js":ads()"#,
+            "!5",
+            r#"c1:%fetch:{1,Window}:"/""#,
+            r#"c2:%getItem:{2,Storage}:"k""#,
+        ]),
+        page(&[
+            "~0x1",
+            // Same URL, different source, and different ID.
+            r#"$7:"https\://a.com/a.js":a(1)"#,
+            "!7",
+            r#"c1:%fetch:{1,Window}:"/""#,
+        ]),
+    ];
+    let consistency = TrialConsistency::new(&trials);
+    assert_eq!(consistency.n_trial, 2);
+    assert_eq!(consistency.scripts.len(), 2);
+
+    let a = &consistency.scripts[0];
+    assert_eq!(a.key, TrialScriptKey::Url("https://a.com/a.js".into()));
+    assert_eq!(a.trials, [0, 1]);
+    let stable: Vec<_> = a.stable_api_calls().collect();
+    assert_eq!(stable.len(), 1);
+    assert_eq!(stable[0].attr.as_deref(), Some("fetch"));
+    let flaky: Vec<_> = a.flaky_api_calls().collect();
+    assert_eq!(flaky.len(), 1);
+    assert_eq!(flaky[0].attr.as_deref(), Some("getItem"));
+    assert_eq!(
+        a.stable_spheres().iter().collect::<Vec<_>>(),
+        [Sphere::HasRequest]
+    );
+    assert_eq!(
+        a.flaky_spheres().iter().collect::<Vec<_>>(),
+        [Sphere::UsesStorage]
+    );
+
+    let ads = &consistency.scripts[1];
+    assert_eq!(ads.trials, [0]);
+    assert_eq!(consistency.scripts_in_all_trials().count(), 1);
+}